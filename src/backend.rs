@@ -0,0 +1,71 @@
+use std::io;
+use std::time::Duration;
+
+use crate::buffer::Buffer;
+use crate::event::Event;
+
+pub(crate) mod ansi;
+#[cfg(feature = "crossterm")]
+mod crossterm;
+#[cfg(feature = "termion")]
+mod termion;
+mod test;
+
+pub use self::ansi::AnsiBackend;
+#[cfg(feature = "crossterm")]
+pub use self::crossterm::CrosstermBackend;
+#[cfg(feature = "termion")]
+pub use self::termion::TermionBackend;
+pub use self::test::{TestBackend, UPDATE_SNAPSHOTS};
+
+// Define what tixel needs from a terminal, so output can go wherever a backend writes it
+pub trait Backend {
+    // Write the drawn cells of a buffer, blank ones included; the cells never drawn into keep
+    // what is already shown
+    fn draw(&mut self, buffer: &Buffer) -> Result<(), io::Error>;
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error>;
+
+    fn show_cursor(&mut self) -> Result<(), io::Error>;
+
+    fn set_cursor(&mut self, x_axis: u16, y_axis: u16) -> Result<(), io::Error>;
+
+    fn clear(&mut self) -> Result<(), io::Error>;
+
+    // Ask the terminal to report clicks, drags and the mouse wheel as events, which dragging
+    // windows and clicking links rely on. Terminals keep reporting until it is disabled again
+    fn enable_mouse_capture(&mut self) -> Result<(), io::Error>;
+
+    fn disable_mouse_capture(&mut self) -> Result<(), io::Error>;
+
+    // Number of columns and rows of the screen
+    fn size(&self) -> Result<(u16, u16), io::Error>;
+
+    // Whether links are written as OSC 8 sequences the terminal turns into clickable links
+    fn supports_links(&self) -> bool;
+
+    fn flush(&mut self) -> Result<(), io::Error>;
+
+    // Wait up to `timeout` for an input event, None when nothing arrived in time
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>, io::Error>;
+}
+
+// Backend writing to the standard output, picking the first enabled of crossterm and termion
+// and falling back to raw ANSI escape sequences
+#[cfg(feature = "crossterm")]
+pub fn stdout() -> Box<dyn Backend> {
+    Box::new(CrosstermBackend::new(io::stdout()))
+}
+
+#[cfg(all(feature = "termion", not(feature = "crossterm")))]
+pub fn stdout() -> Box<dyn Backend> {
+    Box::new(TermionBackend::new(io::stdout()))
+}
+
+#[cfg(not(any(feature = "crossterm", feature = "termion")))]
+pub fn stdout() -> Box<dyn Backend> {
+    Box::new(AnsiBackend::new(
+        io::stdout(),
+        AnsiBackend::<io::Stdout>::env_size(),
+    ))
+}
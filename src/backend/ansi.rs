@@ -0,0 +1,352 @@
+use std::env;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::backend::Backend;
+use crate::buffer::{Buffer, Cell};
+use crate::event::Event;
+use crate::font::{FontStyle, Modifier, Underline};
+use crate::link;
+use crate::{Color, HexColor};
+
+// Turn the reporting of mouse presses, releases and drags on and off, with positions in the
+// SGR encoding and in the urxvt one for terminals lacking it
+pub(crate) const ENABLE_MOUSE_CAPTURE: &str = "\x1b[?1000h\x1b[?1002h\x1b[?1015h\x1b[?1006h";
+pub(crate) const DISABLE_MOUSE_CAPTURE: &str = "\x1b[?1006l\x1b[?1015l\x1b[?1002l\x1b[?1000l";
+
+// Write plain ANSI escape sequences to any writer, e.g. a file, a socket or a string,
+// without touching the terminal; it has no input, so no event ever arrives
+pub struct AnsiBackend<W: Write> {
+    writer: W,
+    size: (u16, u16), // Size reported to the layers, as nothing can be queried
+    links: bool,      // Write links as OSC 8 sequences, off unless enabled
+}
+
+impl<W: Write> AnsiBackend<W> {
+    pub fn new(writer: W, size: (u16, u16)) -> Self {
+        Self {
+            writer,
+            size,
+            links: false,
+        }
+    }
+
+    pub fn links(mut self, links: bool) -> Self {
+        self.links = links;
+        self
+    }
+
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    // Size advertised by the `COLUMNS` and `LINES` environment variables, 80x24 otherwise
+    pub fn env_size() -> (u16, u16) {
+        let read = |name: &str, default: u16| {
+            env::var(name)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        };
+        (read("COLUMNS", 80), read("LINES", 24))
+    }
+}
+
+impl<W: Write> Backend for AnsiBackend<W> {
+    // Only the attributes that change from one cell to the next are written, and the cursor
+    // is only moved when the cells are not next to each other. The terminal covers the second
+    // cell of wide characters by itself
+    fn draw(&mut self, buffer: &Buffer) -> Result<(), io::Error> {
+        let mut pen = Pen::default();
+        let mut links = LinkState::new(self.links);
+        let mut cursor = None;
+        for (x_axis, y_axis, cell) in buffer.drawn() {
+            if cell.is_continuation() {
+                continue;
+            }
+            if cursor != Some((x_axis, y_axis)) {
+                write!(self.writer, "\x1b[{};{}H", y_axis + 1, x_axis + 1)?;
+            }
+            let next = Pen::of(cell);
+            write!(
+                self.writer,
+                "{}{}{}",
+                links.switch(cell.link.as_deref()),
+                pen.transition(&next),
+                cell.symbol
+            )?;
+            pen = next;
+            cursor = Some((x_axis.saturating_add(cell.width() as u16), y_axis));
+        }
+        write!(self.writer, "{}", links.switch(None))?;
+        if pen != Pen::default() {
+            write!(self.writer, "\x1b[0m")?;
+        }
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "\x1b[?25l")
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "\x1b[?25h")
+    }
+
+    fn set_cursor(&mut self, x_axis: u16, y_axis: u16) -> Result<(), io::Error> {
+        write!(self.writer, "\x1b[{};{}H", y_axis + 1, x_axis + 1)
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "\x1b[2J")
+    }
+
+    fn enable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "{}", ENABLE_MOUSE_CAPTURE)
+    }
+
+    fn disable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "{}", DISABLE_MOUSE_CAPTURE)
+    }
+
+    fn size(&self) -> Result<(u16, u16), io::Error> {
+        Ok(self.size)
+    }
+
+    fn supports_links(&self) -> bool {
+        self.links
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.writer.flush()
+    }
+
+    fn poll_event(&mut self, _timeout: Duration) -> Result<Option<Event>, io::Error> {
+        Ok(None)
+    }
+}
+
+// Define the link the terminal attaches to the next characters, so backends only write
+// OSC 8 sequences when it changes and only when links are enabled
+pub(crate) struct LinkState {
+    enabled: bool,
+    current: Option<String>,
+}
+
+impl LinkState {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            current: None,
+        }
+    }
+
+    // Sequence switching to `url`, empty when it is already the current link
+    pub fn switch(&mut self, url: Option<&str>) -> String {
+        if self.current.as_deref() == url || !self.enabled {
+            return String::new();
+        }
+        self.current = url.map(str::to_string);
+        link::osc8(url)
+    }
+}
+
+// Define the colors and style the terminal draws the next characters with
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub(crate) struct Pen {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub style: FontStyle,
+}
+
+impl Pen {
+    pub fn of(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            style: cell.style,
+        }
+    }
+
+    // Escape sequence switching from this pen to `next`, turning off only the attributes
+    // `next` does not have and turning on only the ones this pen lacks; empty when they match
+    pub fn transition(&self, next: &Pen) -> String {
+        let mut codes: Vec<String> = Vec::new();
+        let (from, to) = (self.style.modifiers(), next.style.modifiers());
+        let (removed, added) = (from - to, to - from);
+
+        // Bold and dim are turned off together, so the one that stays is turned back on
+        let intensity = Modifier::BOLD | Modifier::DIM;
+        let restored = if removed.intersects(intensity) {
+            codes.push("22".into());
+            to
+        } else {
+            added
+        };
+        if restored.contains(Modifier::BOLD) {
+            codes.push("1".into());
+        }
+        if restored.contains(Modifier::DIM) {
+            codes.push("2".into());
+        }
+
+        for (modifier, on, off) in [
+            (Modifier::ITALIC, "3", "23"),
+            (Modifier::BLINK, "5", "25"),
+            (Modifier::REVERSE, "7", "27"),
+            (Modifier::HIDDEN, "8", "28"),
+            (Modifier::STRIKETHROUGH, "9", "29"),
+            (Modifier::OVERLINE, "53", "55"),
+        ] {
+            if removed.contains(modifier) {
+                codes.push(off.into());
+            } else if added.contains(modifier) {
+                codes.push(on.into());
+            }
+        }
+
+        if self.style.underline_style() != next.style.underline_style() {
+            codes.push(
+                match next.style.underline_style() {
+                    Underline::None => "24",
+                    Underline::Single => "4",
+                    Underline::Double => "4:2",
+                    Underline::Curly => "4:3",
+                    Underline::Dotted => "4:4",
+                    Underline::Dashed => "4:5",
+                }
+                .into(),
+            );
+        }
+        if self.style.underline_color() != next.style.underline_color() {
+            codes.push(color_code(next.style.underline_color(), 50));
+        }
+        if self.fg != next.fg {
+            codes.push(color_code(next.fg, 30));
+        }
+        if self.bg != next.bg {
+            codes.push(color_code(next.bg, 40));
+        }
+
+        if codes.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", codes.join(";"))
+        }
+    }
+
+    // Apply the parameters of an SGR sequence, e.g. "1;38;5;208" or "4:3", ignoring the
+    // ones tixel does not know
+    pub fn apply_sgr(&mut self, parameters: &str) {
+        let mut items = parameters.split(';');
+        while let Some(item) = items.next() {
+            let mut fields = item.split(':').map(|field| field.parse::<u16>().ok());
+            let code = fields.next().flatten().unwrap_or(0);
+            let style = self.style;
+
+            match code {
+                0 => *self = Self::default(),
+                1 => self.style = style.patch(Modifier::BOLD),
+                2 => self.style = style.patch(Modifier::DIM),
+                3 => self.style = style.patch(Modifier::ITALIC),
+                4 => {
+                    let underline = match fields.next().flatten() {
+                        Some(0) => Underline::None,
+                        Some(2) => Underline::Double,
+                        Some(3) => Underline::Curly,
+                        Some(4) => Underline::Dotted,
+                        Some(5) => Underline::Dashed,
+                        _ => Underline::Single,
+                    };
+                    self.style = style.with_underline(underline);
+                }
+                5 | 6 => self.style = style.patch(Modifier::BLINK),
+                7 => self.style = style.patch(Modifier::REVERSE),
+                8 => self.style = style.patch(Modifier::HIDDEN),
+                9 => self.style = style.patch(Modifier::STRIKETHROUGH),
+                21 => self.style = style.with_underline(Underline::Double),
+                22 => self.style = style.remove(Modifier::BOLD | Modifier::DIM),
+                23 => self.style = style.remove(Modifier::ITALIC),
+                24 => self.style = style.with_underline(Underline::None),
+                25 => self.style = style.remove(Modifier::BLINK),
+                27 => self.style = style.remove(Modifier::REVERSE),
+                28 => self.style = style.remove(Modifier::HIDDEN),
+                29 => self.style = style.remove(Modifier::STRIKETHROUGH),
+                53 => self.style = style.patch(Modifier::OVERLINE),
+                55 => self.style = style.remove(Modifier::OVERLINE),
+                30..=37 => self.fg = Some(Color::NAMED[code as usize - 30]),
+                40..=47 => self.bg = Some(Color::NAMED[code as usize - 40]),
+                90..=97 => self.fg = Some(Color::NAMED[code as usize - 90 + 8]),
+                100..=107 => self.bg = Some(Color::NAMED[code as usize - 100 + 8]),
+                39 => self.fg = None,
+                49 => self.bg = None,
+                59 => self.style = style.with_underline_color(None),
+                38 | 48 | 58 => {
+                    // Either "38;5;n" or the colon form "38:5:n", with an optional color
+                    // space before the channels of "38:2::r:g:b"
+                    let mut values: Vec<u16> = if item.contains(':') {
+                        let mut values: Vec<Option<u16>> = fields.collect();
+                        if values.len() == 5 && values.first() == Some(&Some(2)) {
+                            values.remove(1);
+                        }
+                        values.into_iter().map(|value| value.unwrap_or(0)).collect()
+                    } else {
+                        let count = match items.clone().next() {
+                            Some("5") => 2,
+                            Some("2") => 4,
+                            _ => 0,
+                        };
+                        items
+                            .by_ref()
+                            .take(count)
+                            .map(|value| value.parse().unwrap_or(0))
+                            .collect()
+                    };
+                    values.resize(4, 0);
+                    let channel = |value: u16| value.min(255) as u8;
+                    let color = match values[0] {
+                        5 => Color::Indexed(channel(values[1])),
+                        2 => HexColor::from_rgb(
+                            channel(values[1]),
+                            channel(values[2]),
+                            channel(values[3]),
+                        )
+                        .into(),
+                        _ => continue,
+                    };
+                    match code {
+                        38 => self.fg = Some(color),
+                        48 => self.bg = Some(color),
+                        _ => self.style = style.with_underline_color(color),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// SGR parameters selecting a color, `base` being 30 for text, 40 for the background and
+// 50 for the underline, which only takes indexed and RGB colors
+fn color_code(color: Option<Color>, base: u8) -> String {
+    match color {
+        None | Some(Color::Reset) => format!("{}", base + 9),
+        Some(Color::Rgb(color)) => {
+            let (r, g, b) = color.components();
+            format!("{};2;{};{};{}", base + 8, r, g, b)
+        }
+        Some(color) => match color.index().unwrap_or(0) {
+            index @ 0..=7 if base != 50 => format!("{}", base + index),
+            index @ 8..=15 if base != 50 => format!("{}", base + 60 + index - 8),
+            index => format!("{};5;{}", base + 8, index),
+        },
+    }
+}
@@ -0,0 +1,186 @@
+use std::io::{self, Write};
+use std::time::Duration;
+
+use ::crossterm::event as crossterm_event;
+use ::crossterm::{cursor, queue, style, terminal};
+
+use crate::backend::ansi::{LinkState, Pen};
+use crate::backend::Backend;
+use crate::buffer::Buffer;
+use crate::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use crate::link;
+
+// Draw through crossterm, which also works on the legacy Windows console
+pub struct CrosstermBackend<W: Write> {
+    writer: W,
+    links: bool, // Write links as OSC 8 sequences, detected from the environment by default
+}
+
+impl<W: Write> CrosstermBackend<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            links: link::supported(),
+        }
+    }
+
+    pub fn links(mut self, links: bool) -> Self {
+        self.links = links;
+        self
+    }
+}
+
+impl<W: Write> Backend for CrosstermBackend<W> {
+    // Only the attributes that change from one cell to the next are sent
+    fn draw(&mut self, buffer: &Buffer) -> Result<(), io::Error> {
+        let mut pen = Pen::default();
+        let mut links = LinkState::new(self.links);
+        for (x_axis, y_axis, cell) in buffer.drawn() {
+            // The terminal covers the second cell of wide characters by itself
+            if cell.is_continuation() {
+                continue;
+            }
+            queue!(self.writer, cursor::MoveTo(x_axis, y_axis))?;
+            let next = Pen::of(cell);
+            // The SGR sequences are shared with the other backends, and crossterm has no
+            // command for links, so both are printed as is
+            queue!(
+                self.writer,
+                style::Print(links.switch(cell.link.as_deref())),
+                style::Print(pen.transition(&next)),
+                style::Print(&cell.symbol)
+            )?;
+            pen = next;
+        }
+        queue!(self.writer, style::Print(links.switch(None)))?;
+        if pen != Pen::default() {
+            queue!(
+                self.writer,
+                style::SetAttribute(style::Attribute::Reset),
+                style::ResetColor
+            )?;
+        }
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        queue!(self.writer, cursor::Hide)
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        queue!(self.writer, cursor::Show)
+    }
+
+    fn set_cursor(&mut self, x_axis: u16, y_axis: u16) -> Result<(), io::Error> {
+        queue!(self.writer, cursor::MoveTo(x_axis, y_axis))
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        queue!(self.writer, terminal::Clear(terminal::ClearType::All))
+    }
+
+    fn enable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        queue!(self.writer, crossterm_event::EnableMouseCapture)
+    }
+
+    fn disable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        queue!(self.writer, crossterm_event::DisableMouseCapture)
+    }
+
+    fn size(&self) -> Result<(u16, u16), io::Error> {
+        terminal::size()
+    }
+
+    fn supports_links(&self) -> bool {
+        self.links
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.writer.flush()
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>, io::Error> {
+        if !crossterm_event::poll(timeout)? {
+            return Ok(None);
+        }
+        Ok(convert_event(crossterm_event::read()?))
+    }
+}
+
+// Translate a crossterm event, dropping the ones tixel has no equivalent for
+fn convert_event(event: crossterm_event::Event) -> Option<Event> {
+    match event {
+        crossterm_event::Event::Key(key) if key.kind == crossterm_event::KeyEventKind::Press => {
+            let code = match key.code {
+                crossterm_event::KeyCode::Char(symbol) => KeyCode::Char(symbol),
+                crossterm_event::KeyCode::Enter => KeyCode::Enter,
+                crossterm_event::KeyCode::Esc => KeyCode::Esc,
+                crossterm_event::KeyCode::Backspace => KeyCode::Backspace,
+                crossterm_event::KeyCode::Tab => KeyCode::Tab,
+                crossterm_event::KeyCode::BackTab => KeyCode::BackTab,
+                crossterm_event::KeyCode::Left => KeyCode::Left,
+                crossterm_event::KeyCode::Right => KeyCode::Right,
+                crossterm_event::KeyCode::Up => KeyCode::Up,
+                crossterm_event::KeyCode::Down => KeyCode::Down,
+                crossterm_event::KeyCode::Home => KeyCode::Home,
+                crossterm_event::KeyCode::End => KeyCode::End,
+                crossterm_event::KeyCode::PageUp => KeyCode::PageUp,
+                crossterm_event::KeyCode::PageDown => KeyCode::PageDown,
+                crossterm_event::KeyCode::Insert => KeyCode::Insert,
+                crossterm_event::KeyCode::Delete => KeyCode::Delete,
+                crossterm_event::KeyCode::F(number) => KeyCode::F(number),
+                _ => return None,
+            };
+            Some(Event::Key(KeyEvent::new(
+                code,
+                convert_modifiers(key.modifiers),
+            )))
+        }
+        crossterm_event::Event::Mouse(mouse) => {
+            let kind = match mouse.kind {
+                crossterm_event::MouseEventKind::Down(button) => {
+                    MouseEventKind::Down(convert_button(button))
+                }
+                crossterm_event::MouseEventKind::Up(button) => {
+                    MouseEventKind::Up(convert_button(button))
+                }
+                crossterm_event::MouseEventKind::Drag(button) => {
+                    MouseEventKind::Drag(convert_button(button))
+                }
+                crossterm_event::MouseEventKind::Moved => MouseEventKind::Moved,
+                crossterm_event::MouseEventKind::ScrollUp => MouseEventKind::ScrollUp,
+                crossterm_event::MouseEventKind::ScrollDown => MouseEventKind::ScrollDown,
+                _ => return None,
+            };
+            Some(Event::Mouse(MouseEvent {
+                kind,
+                column: mouse.column,
+                row: mouse.row,
+                modifiers: convert_modifiers(mouse.modifiers),
+            }))
+        }
+        crossterm_event::Event::Resize(columns, rows) => Some(Event::Resize(columns, rows)),
+        crossterm_event::Event::Paste(text) => Some(Event::Paste(text)),
+        crossterm_event::Event::FocusGained => Some(Event::FocusGained),
+        crossterm_event::Event::FocusLost => Some(Event::FocusLost),
+        crossterm_event::Event::Key(_) => None,
+    }
+}
+
+fn convert_modifiers(modifiers: crossterm_event::KeyModifiers) -> KeyModifiers {
+    KeyModifiers::new(
+        modifiers.contains(crossterm_event::KeyModifiers::SHIFT),
+        modifiers.contains(crossterm_event::KeyModifiers::CONTROL),
+        modifiers.contains(crossterm_event::KeyModifiers::ALT),
+    )
+}
+
+fn convert_button(button: crossterm_event::MouseButton) -> MouseButton {
+    match button {
+        crossterm_event::MouseButton::Left => MouseButton::Left,
+        crossterm_event::MouseButton::Right => MouseButton::Right,
+        crossterm_event::MouseButton::Middle => MouseButton::Middle,
+    }
+}
@@ -0,0 +1,214 @@
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ::termion::event as termion_event;
+use ::termion::input::{Events, TermRead};
+use ::termion::{clear, cursor, style, AsyncReader};
+
+use crate::backend::ansi::{LinkState, Pen, DISABLE_MOUSE_CAPTURE, ENABLE_MOUSE_CAPTURE};
+use crate::backend::Backend;
+use crate::buffer::Buffer;
+use crate::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use crate::link;
+
+// How often the standard input is checked while waiting for an event
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// Draw through termion, a pure Rust backend for Unix terminals
+pub struct TermionBackend<W: Write> {
+    writer: W,
+    events: Option<Events<AsyncReader>>, // Opened on the first poll, as it spawns a reader thread
+    links: bool, // Write links as OSC 8 sequences, detected from the environment by default
+}
+
+impl<W: Write> TermionBackend<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            events: None,
+            links: link::supported(),
+        }
+    }
+
+    pub fn links(mut self, links: bool) -> Self {
+        self.links = links;
+        self
+    }
+}
+
+impl<W: Write> Backend for TermionBackend<W> {
+    // Termion writes plain escape sequences, so the attributes are switched the same way
+    // as `AnsiBackend` does, changing only the ones that differ from the previous cell
+    fn draw(&mut self, buffer: &Buffer) -> Result<(), io::Error> {
+        let mut pen = Pen::default();
+        let mut links = LinkState::new(self.links);
+        for (x_axis, y_axis, cell) in buffer.drawn() {
+            // The terminal covers the second cell of wide characters by itself
+            if cell.is_continuation() {
+                continue;
+            }
+            // Termion positions are one-based
+            let next = Pen::of(cell);
+            write!(
+                self.writer,
+                "{}{}{}{}",
+                cursor::Goto(x_axis + 1, y_axis + 1),
+                links.switch(cell.link.as_deref()),
+                pen.transition(&next),
+                cell.symbol
+            )?;
+            pen = next;
+        }
+        write!(self.writer, "{}", links.switch(None))?;
+        if pen != Pen::default() {
+            write!(self.writer, "{}", style::Reset)?;
+        }
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "{}", cursor::Hide)
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "{}", cursor::Show)
+    }
+
+    fn set_cursor(&mut self, x_axis: u16, y_axis: u16) -> Result<(), io::Error> {
+        write!(self.writer, "{}", cursor::Goto(x_axis + 1, y_axis + 1))
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "{}", clear::All)
+    }
+
+    // The sequences `termion::input::MouseTerminal` writes, sent on demand instead of for the
+    // lifetime of a wrapped writer
+    fn enable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "{}", ENABLE_MOUSE_CAPTURE)
+    }
+
+    fn disable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        write!(self.writer, "{}", DISABLE_MOUSE_CAPTURE)
+    }
+
+    fn size(&self) -> Result<(u16, u16), io::Error> {
+        ::termion::terminal_size()
+    }
+
+    fn supports_links(&self) -> bool {
+        self.links
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.writer.flush()
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>, io::Error> {
+        let events = self
+            .events
+            .get_or_insert_with(|| ::termion::async_stdin().events());
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match events.next() {
+                Some(Ok(event)) => {
+                    if let Some(event) = convert_event(event) {
+                        return Ok(Some(event));
+                    }
+                }
+                Some(Err(error)) => return Err(error),
+                None if Instant::now() >= deadline => return Ok(None),
+                None => thread::sleep(POLL_INTERVAL),
+            }
+        }
+    }
+}
+
+// Translate a termion event, dropping the ones tixel has no equivalent for
+fn convert_event(event: termion_event::Event) -> Option<Event> {
+    match event {
+        termion_event::Event::Key(key) => {
+            let (code, modifiers) = convert_key(key)?;
+            Some(Event::Key(KeyEvent::new(code, modifiers)))
+        }
+        termion_event::Event::Mouse(mouse) => {
+            // Termion neither reports which button was released or held, nor the modifiers
+            let (kind, column, row) = match mouse {
+                termion_event::MouseEvent::Press(button, column, row) => {
+                    let kind = match button {
+                        termion_event::MouseButton::Left => MouseEventKind::Down(MouseButton::Left),
+                        termion_event::MouseButton::Right => {
+                            MouseEventKind::Down(MouseButton::Right)
+                        }
+                        termion_event::MouseButton::Middle => {
+                            MouseEventKind::Down(MouseButton::Middle)
+                        }
+                        termion_event::MouseButton::WheelUp => MouseEventKind::ScrollUp,
+                        termion_event::MouseButton::WheelDown => MouseEventKind::ScrollDown,
+                        _ => return None,
+                    };
+                    (kind, column, row)
+                }
+                termion_event::MouseEvent::Release(column, row) => {
+                    (MouseEventKind::Up(MouseButton::Left), column, row)
+                }
+                termion_event::MouseEvent::Hold(column, row) => {
+                    (MouseEventKind::Drag(MouseButton::Left), column, row)
+                }
+            };
+            Some(Event::Mouse(MouseEvent {
+                kind,
+                column: column.saturating_sub(1),
+                row: row.saturating_sub(1),
+                modifiers: KeyModifiers::NONE,
+            }))
+        }
+        termion_event::Event::Unsupported(_) => None,
+    }
+}
+
+fn convert_key(key: termion_event::Key) -> Option<(KeyCode, KeyModifiers)> {
+    use termion_event::Key;
+
+    let key = match key {
+        Key::Char('\n') => (KeyCode::Enter, KeyModifiers::NONE),
+        Key::Char('\t') => (KeyCode::Tab, KeyModifiers::NONE),
+        Key::Char(symbol) => (KeyCode::Char(symbol), KeyModifiers::NONE),
+        Key::Alt(symbol) => (KeyCode::Char(symbol), KeyModifiers::ALT),
+        Key::Ctrl(symbol) => (KeyCode::Char(symbol), KeyModifiers::CONTROL),
+        Key::Esc => (KeyCode::Esc, KeyModifiers::NONE),
+        Key::Backspace => (KeyCode::Backspace, KeyModifiers::NONE),
+        Key::BackTab => (KeyCode::BackTab, KeyModifiers::SHIFT),
+        Key::Left => (KeyCode::Left, KeyModifiers::NONE),
+        Key::ShiftLeft => (KeyCode::Left, KeyModifiers::SHIFT),
+        Key::AltLeft => (KeyCode::Left, KeyModifiers::ALT),
+        Key::CtrlLeft => (KeyCode::Left, KeyModifiers::CONTROL),
+        Key::Right => (KeyCode::Right, KeyModifiers::NONE),
+        Key::ShiftRight => (KeyCode::Right, KeyModifiers::SHIFT),
+        Key::AltRight => (KeyCode::Right, KeyModifiers::ALT),
+        Key::CtrlRight => (KeyCode::Right, KeyModifiers::CONTROL),
+        Key::Up => (KeyCode::Up, KeyModifiers::NONE),
+        Key::ShiftUp => (KeyCode::Up, KeyModifiers::SHIFT),
+        Key::AltUp => (KeyCode::Up, KeyModifiers::ALT),
+        Key::CtrlUp => (KeyCode::Up, KeyModifiers::CONTROL),
+        Key::Down => (KeyCode::Down, KeyModifiers::NONE),
+        Key::ShiftDown => (KeyCode::Down, KeyModifiers::SHIFT),
+        Key::AltDown => (KeyCode::Down, KeyModifiers::ALT),
+        Key::CtrlDown => (KeyCode::Down, KeyModifiers::CONTROL),
+        Key::Home => (KeyCode::Home, KeyModifiers::NONE),
+        Key::CtrlHome => (KeyCode::Home, KeyModifiers::CONTROL),
+        Key::End => (KeyCode::End, KeyModifiers::NONE),
+        Key::CtrlEnd => (KeyCode::End, KeyModifiers::CONTROL),
+        Key::PageUp => (KeyCode::PageUp, KeyModifiers::NONE),
+        Key::PageDown => (KeyCode::PageDown, KeyModifiers::NONE),
+        Key::Insert => (KeyCode::Insert, KeyModifiers::NONE),
+        Key::Delete => (KeyCode::Delete, KeyModifiers::NONE),
+        Key::F(number) => (KeyCode::F(number), KeyModifiers::NONE),
+        _ => return None,
+    };
+    Some(key)
+}
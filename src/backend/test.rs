@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::backend::Backend;
+use crate::buffer::{Buffer, Cell};
+use crate::event::Event;
+use crate::export::{self, ExportFormat};
+use crate::font::FontStyle;
+use crate::layer::Layer;
+use crate::layout::Rect;
+use crate::symbols;
+use crate::Color;
+
+// Set to any value to rewrite golden files instead of comparing against them
+pub const UPDATE_SNAPSHOTS: &str = "TIXEL_UPDATE_SNAPSHOTS";
+
+// Keep the rendered frames in memory instead of writing them to a terminal,
+// so rendering can be checked without a TTY
+pub struct TestBackend {
+    frame: Buffer,           // Cells as a terminal would show them after the last draw
+    cursor: (u16, u16),      // Position the cursor was last moved to
+    cursor_visible: bool,    // Indicates if the cursor is shown
+    mouse_captured: bool,    // Whether mouse reporting was enabled
+    events: VecDeque<Event>, // Input waiting to be polled, queued by `push_event`
+}
+
+impl TestBackend {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            frame: Buffer::new(Rect::new(0, 0, width, height)),
+            cursor: (0, 0),
+            cursor_visible: true,
+            mouse_captured: false,
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn area(&self) -> Rect {
+        self.frame.area()
+    }
+
+    // Change the size of the screen, clearing it like a terminal resize would
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.frame = Buffer::new(Rect::new(0, 0, width, height));
+    }
+
+    pub fn cursor(&self) -> (u16, u16) {
+        self.cursor
+    }
+
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    pub fn mouse_captured(&self) -> bool {
+        self.mouse_captured
+    }
+
+    // Queue an input event for the next `poll_event`, to simulate the user
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push_back(event);
+    }
+
+    pub fn frame(&self) -> &Buffer {
+        &self.frame
+    }
+
+    pub(crate) fn frame_mut(&mut self) -> &mut Buffer {
+        &mut self.frame
+    }
+
+    pub fn cell(&self, x_axis: u16, y_axis: u16) -> Option<&Cell> {
+        self.frame.get(x_axis, y_axis)
+    }
+
+    // Draw a layer over the whole screen
+    pub fn render(&mut self, layer: &dyn Layer) -> Result<(), io::Error> {
+        let mut buffer = Buffer::new(self.area());
+        layer.draw(&mut buffer)?;
+        self.draw(&buffer)
+    }
+
+    // The symbols of the frame, one line per row; wide characters are written once
+    pub fn to_text(&self) -> String {
+        export::to_text(&self.frame)
+    }
+
+    // The frame with SGR escape sequences for colors and styles, one line per row
+    pub fn to_ansi(&self) -> String {
+        export::to_ansi(&self.frame, false)
+    }
+
+    // Check the symbols of the cells covered by a string, starting at a position
+    pub fn assert_text(&self, x_axis: u16, y_axis: u16, expected: &str) {
+        let actual: String = (0..symbols::text_width(expected) as u16)
+            .map(|offset| {
+                self.frame
+                    .get(x_axis + offset, y_axis)
+                    .map_or(" ", |cell| cell.symbol.as_str())
+            })
+            .collect();
+        assert!(
+            actual == expected,
+            "text at ({}, {}) is {:?}, expected {:?}\n{}",
+            x_axis,
+            y_axis,
+            actual,
+            expected,
+            self.to_text()
+        );
+    }
+
+    // Check the whole frame, one string per row
+    pub fn assert_lines(&self, expected: &[&str]) {
+        let actual = self.to_text();
+        let actual: Vec<&str> = actual.lines().collect();
+        assert!(
+            actual == expected,
+            "frame differs\nexpected:\n{}\nactual:\n{}",
+            expected.join("\n"),
+            actual.join("\n")
+        );
+    }
+
+    pub fn assert_fg(&self, x_axis: u16, y_axis: u16, expected: Option<Color>) {
+        let actual = self.frame.get(x_axis, y_axis).and_then(|cell| cell.fg);
+        assert!(
+            actual == expected,
+            "foreground at ({}, {}) is {}, expected {}",
+            x_axis,
+            y_axis,
+            describe(actual),
+            describe(expected)
+        );
+    }
+
+    pub fn assert_bg(&self, x_axis: u16, y_axis: u16, expected: Option<Color>) {
+        let actual = self.frame.get(x_axis, y_axis).and_then(|cell| cell.bg);
+        assert!(
+            actual == expected,
+            "background at ({}, {}) is {}, expected {}",
+            x_axis,
+            y_axis,
+            describe(actual),
+            describe(expected)
+        );
+    }
+
+    pub fn assert_style(&self, x_axis: u16, y_axis: u16, expected: FontStyle) {
+        let actual = self.frame.get(x_axis, y_axis).map(|cell| cell.style);
+        assert!(
+            actual == Some(expected),
+            "style at ({}, {}) is {:?}, expected {:?}",
+            x_axis,
+            y_axis,
+            actual,
+            expected
+        );
+    }
+
+    // Compare the frame with a golden file: files `export::save` writes as ANSI (`.ans` and
+    // `.ansi`) hold the ANSI rendering, any other extension the plain text. Missing golden
+    // files are written, as are all of them when the `TIXEL_UPDATE_SNAPSHOTS` environment
+    // variable is set.
+    pub fn assert_snapshot(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let actual = match ExportFormat::from_path(path) {
+            Some(ExportFormat::Ansi { .. }) => self.to_ansi(),
+            _ => self.to_text(),
+        };
+
+        if env::var_os(UPDATE_SNAPSHOTS).is_some() || !path.exists() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).expect("Unable to create the snapshot directory");
+            }
+            fs::write(path, &actual).expect("Unable to write the snapshot");
+            return;
+        }
+
+        let expected = fs::read_to_string(path).expect("Unable to read the snapshot");
+        assert!(
+            actual == expected,
+            "frame differs from {}, set {} to update it\nexpected:\n{}\nactual:\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS,
+            expected,
+            actual
+        );
+    }
+}
+
+impl Backend for TestBackend {
+    fn draw(&mut self, buffer: &Buffer) -> Result<(), io::Error> {
+        for (x_axis, y_axis, cell) in buffer.drawn() {
+            if let Some(target) = self.frame.get_mut(x_axis, y_axis) {
+                *target = cell.clone();
+            }
+        }
+        // Like a terminal, drop what is left of wide characters partly drawn over
+        let area = self.frame.area();
+        for y_axis in area.y..area.y + area.height {
+            self.frame.mend(area.x..area.x + area.width, y_axis);
+        }
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        self.cursor_visible = false;
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        self.cursor_visible = true;
+        Ok(())
+    }
+
+    fn set_cursor(&mut self, x_axis: u16, y_axis: u16) -> Result<(), io::Error> {
+        self.cursor = (x_axis, y_axis);
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        self.frame = Buffer::new(self.frame.area());
+        Ok(())
+    }
+
+    fn enable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        self.mouse_captured = true;
+        Ok(())
+    }
+
+    fn disable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        self.mouse_captured = false;
+        Ok(())
+    }
+
+    fn size(&self) -> Result<(u16, u16), io::Error> {
+        let area = self.frame.area();
+        Ok((area.width, area.height))
+    }
+
+    // Links stay in the cells, but are never written out, so snapshots do not depend on
+    // the terminal running the tests
+    fn supports_links(&self) -> bool {
+        false
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    // Queued events are returned at once, without waiting for the timeout
+    fn poll_event(&mut self, _timeout: Duration) -> Result<Option<Event>, io::Error> {
+        Ok(self.events.pop_front())
+    }
+}
+
+fn describe(color: Option<Color>) -> String {
+    color.map_or_else(|| "the default".to_string(), |color| color.to_string())
+}
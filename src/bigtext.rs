@@ -0,0 +1,478 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::buffer::Buffer;
+use crate::font::{FontStyle, TextAlignment};
+use crate::gradient::Gradient;
+use crate::layout::{Direction, Rect};
+use crate::symbols;
+use crate::Color;
+
+// Rows of the glyphs of the built-in fonts, in pixels
+const GLYPH_HEIGHT: usize = 5;
+
+// Characters every FIGlet font defines after printable ASCII: Ä Ö Ü ä ö ü ß
+const DEUTSCH: [u32; 7] = [196, 214, 220, 228, 246, 252, 223];
+
+// Define the fonts big text can be drawn with
+#[derive(Clone, Debug, Default)]
+pub enum BigFont {
+    #[default]
+    Block, // Built-in font drawn with full blocks, one pixel per cell
+    HalfBlock, // Built-in font drawn with half blocks, two pixels per cell stacked vertically
+    Figlet(FigFont),
+}
+
+// Define a font read from a FIGlet `.flf` file
+#[derive(Clone, Debug)]
+pub struct FigFont {
+    height: usize,
+    glyphs: HashMap<char, Vec<Vec<char>>>, // Rows of each glyph, hard blanks turned into `\0`
+}
+
+impl FigFont {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, io::Error> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(source: &str) -> Result<Self, io::Error> {
+        let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidData, reason.to_string());
+        let mut lines = source.lines();
+
+        // Header such as `flf2a$ 6 5 16 15 11`: signature and hard blank, height, baseline,
+        // longest line, old layout and number of comment lines
+        let header = lines.next().ok_or_else(|| invalid("empty FIGlet font"))?;
+        let signature = header
+            .strip_prefix("flf2a")
+            .ok_or_else(|| invalid("missing the flf2a signature"))?;
+        let hardblank = signature
+            .chars()
+            .next()
+            .ok_or_else(|| invalid("missing the hard blank"))?;
+        let fields: Vec<&str> = signature[hardblank.len_utf8()..]
+            .split_whitespace()
+            .collect();
+        let field = |index: usize| -> Result<usize, io::Error> {
+            fields
+                .get(index)
+                .and_then(|field| field.parse().ok())
+                .ok_or_else(|| invalid("malformed FIGlet header"))
+        };
+        let height = field(0)?;
+        let comments = field(4)?;
+        if height == 0 {
+            return Err(invalid("FIGlet font with a height of 0"));
+        }
+
+        let mut lines = lines.skip(comments);
+        let mut glyphs = HashMap::new();
+        let read_glyph = |lines: &mut dyn Iterator<Item = &str>| -> Option<Vec<Vec<char>>> {
+            (0..height)
+                .map(|_| {
+                    let line = lines.next()?.trim_end_matches('\r');
+                    // Each row ends with one or more end marks, usually `@`
+                    let mark = line.chars().last()?;
+                    let row = line.trim_end_matches(mark);
+                    Some(
+                        row.chars()
+                            .map(|symbol| if symbol == hardblank { '\0' } else { symbol })
+                            .collect(),
+                    )
+                })
+                .collect()
+        };
+
+        for code in (32..127).chain(DEUTSCH) {
+            let Some(glyph) = read_glyph(&mut lines) else {
+                break;
+            };
+            if let Some(symbol) = char::from_u32(code) {
+                glyphs.insert(symbol, glyph);
+            }
+        }
+
+        // Code-tagged glyphs: a line starting with the character code, then the rows
+        while let Some(tag) = lines.next() {
+            let code = tag.split_whitespace().next().and_then(parse_code);
+            let Some(glyph) = read_glyph(&mut lines) else {
+                break;
+            };
+            if let Some(symbol) = code.and_then(char::from_u32) {
+                glyphs.insert(symbol, glyph);
+            }
+        }
+
+        if glyphs.is_empty() {
+            return Err(invalid("FIGlet font without glyphs"));
+        }
+        Ok(Self { height, glyphs })
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Rows of `text`, each glyph moved left until it touches the previous one
+    fn render(&self, text: &str) -> Vec<Vec<char>> {
+        let mut rows = vec![Vec::new(); self.height];
+        for glyph in text.chars().filter_map(|symbol| self.glyphs.get(&symbol)) {
+            let overlap = rows
+                .iter()
+                .zip(glyph)
+                .map(|(row, line): (&Vec<char>, &Vec<char>)| {
+                    let trailing = row
+                        .iter()
+                        .rev()
+                        .take_while(|symbol| **symbol == ' ')
+                        .count();
+                    let leading = line.iter().take_while(|symbol| **symbol == ' ').count();
+                    trailing + leading
+                })
+                .min()
+                .unwrap_or(0);
+
+            for (row, line) in rows.iter_mut().zip(glyph) {
+                let mut remove = overlap;
+                while remove > 0 && row.last() == Some(&' ') {
+                    row.pop();
+                    remove -= 1;
+                }
+                row.extend(line.iter().skip(remove.min(line.len())));
+            }
+        }
+
+        // Hard blanks only keep glyphs apart, they are drawn as blanks
+        for row in &mut rows {
+            for symbol in row.iter_mut().filter(|symbol| **symbol == '\0') {
+                *symbol = ' ';
+            }
+        }
+        rows
+    }
+}
+
+// Parse a code tag, written in decimal, in hexadecimal after `0x` or in octal after `0`
+fn parse_code(code: &str) -> Option<u32> {
+    if let Some(hex) = code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16).ok()
+    } else if code.len() > 1 && code.starts_with('0') {
+        u32::from_str_radix(&code[1..], 8).ok()
+    } else {
+        code.parse().ok()
+    }
+}
+
+// Define text drawn in large letters, for headers and splash screens. The text is drawn as
+// large as the area allows, falling back to smaller fonts and finally to plain text
+#[derive(Clone)]
+pub struct BigText {
+    pub text: String, // One row of letters per line
+    pub font: BigFont,
+    pub scale: Option<u16>, // Size of the pixels of built-in fonts, as large as fits when None
+    pub alignment: TextAlignment,
+    pub color: Color,
+    pub gradient: Option<Gradient>, // Replaces the color, running across each line of letters
+    pub gradient_direction: Direction, // Horizontal runs the gradient from left to right
+    pub style: FontStyle,
+}
+
+impl BigText {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            font: BigFont::default(),
+            scale: None,
+            alignment: TextAlignment::default(),
+            color: Color::Reset,
+            gradient: None,
+            gradient_direction: Direction::Horizontal,
+            style: FontStyle::default(),
+        }
+    }
+
+    pub fn font(mut self, font: BigFont) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn scale(mut self, scale: u16) -> Self {
+        self.scale = Some(scale.max(1));
+        self
+    }
+
+    pub fn alignment(mut self, alignment: TextAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn color(mut self, color: impl Into<Color>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn gradient(mut self, gradient: Gradient, direction: Direction) -> Self {
+        self.gradient = Some(gradient);
+        self.gradient_direction = direction;
+        self
+    }
+
+    pub fn style(mut self, style: impl Into<FontStyle>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    // Width and height taken by the text with its font at the smallest scale
+    pub fn size(&self) -> (u16, u16) {
+        size_of(&self.lines(&self.font, 1))
+    }
+
+    // Draw the letters from the top of `area`, aligning each line of letters; blanks of the
+    // letters leave the cells below untouched
+    pub fn render(&self, buffer: &mut Buffer, area: Rect) {
+        let lines = self.fit(area);
+        let mut y_axis = area.y;
+
+        for rows in &lines {
+            // Rows are drawn a grapheme cluster at a time, as the plain text fallback may hold
+            // combining marks and wide characters
+            let rows: Vec<String> = rows.iter().map(|row| row.iter().collect()).collect();
+            let width = rows
+                .iter()
+                .map(|row| symbols::text_width(row))
+                .max()
+                .unwrap_or(0);
+            let free = (area.width as usize).saturating_sub(width);
+            let offset = match self.alignment {
+                TextAlignment::Left => 0,
+                TextAlignment::Center => free / 2,
+                TextAlignment::Right => free,
+            } as u16;
+
+            for (row_index, row) in rows.iter().enumerate() {
+                if y_axis >= area.y + area.height {
+                    return;
+                }
+                let mut column = 0;
+                for symbol in row.graphemes(true) {
+                    let start = column;
+                    let x_axis = area.x + offset + start as u16;
+                    let symbol_width = symbols::grapheme_width(symbol);
+                    column += symbol_width;
+                    if x_axis as usize + symbol_width > (area.x + area.width) as usize {
+                        break;
+                    }
+                    if symbol == " " {
+                        continue;
+                    }
+                    let position = match self.gradient_direction {
+                        Direction::Horizontal => fraction(start, width),
+                        Direction::Vertical => fraction(row_index, rows.len()),
+                    };
+                    let color = match &self.gradient {
+                        Some(gradient) => gradient.at(position).into(),
+                        None => self.color,
+                    };
+                    buffer.set_symbol(x_axis, y_axis, symbol, color);
+                    if let Some(cell) = buffer.get_mut(x_axis, y_axis) {
+                        cell.style = self.style;
+                    }
+                }
+                y_axis += 1;
+            }
+        }
+    }
+
+    // Lines of letters in the largest rendition fitting in `area`
+    fn fit(&self, area: Rect) -> Vec<Vec<Vec<char>>> {
+        let fits = |lines: &[Vec<Vec<char>>]| {
+            let (width, height) = size_of(lines);
+            width <= area.width && height <= area.height
+        };
+
+        let mut fallbacks = Vec::new();
+        if let BigFont::Figlet(_) = self.font {
+            fallbacks.push((&self.font, 1));
+        } else {
+            let (width, height) = self.size();
+            let largest = (area.width / width.max(1)).min(area.height / height.max(1));
+            let scale = self.scale.unwrap_or(largest).max(1);
+            fallbacks.extend((1..=scale).rev().map(|scale| (&self.font, scale)));
+        }
+        fallbacks.push((&BigFont::Block, 1));
+        fallbacks.push((&BigFont::HalfBlock, 1));
+
+        fallbacks
+            .into_iter()
+            .map(|(font, scale)| self.lines(font, scale))
+            .find(|lines| fits(lines))
+            .unwrap_or_else(|| {
+                self.text
+                    .lines()
+                    .map(|line| vec![line.chars().collect()])
+                    .collect()
+            })
+    }
+
+    // Rows of every line of the text drawn with `font`
+    fn lines(&self, font: &BigFont, scale: u16) -> Vec<Vec<Vec<char>>> {
+        self.text
+            .lines()
+            .map(|line| match font {
+                BigFont::Figlet(figlet) => figlet.render(line),
+                BigFont::Block => block_rows(&scale_pixels(&pixels(line), scale)),
+                BigFont::HalfBlock => half_block_rows(&scale_pixels(&pixels(line), scale)),
+            })
+            .collect()
+    }
+}
+
+fn size_of(lines: &[Vec<Vec<char>>]) -> (u16, u16) {
+    let width = lines.iter().flatten().map(Vec::len).max().unwrap_or(0);
+    let height: usize = lines.iter().map(Vec::len).sum();
+    (width as u16, height as u16)
+}
+
+// Position of `index` among `count` items, from 0.0 to 1.0
+fn fraction(index: usize, count: usize) -> f64 {
+    if count > 1 {
+        index as f64 / (count - 1) as f64
+    } else {
+        0.0
+    }
+}
+
+// Pixels of a line of text in the built-in font, glyphs one pixel apart
+fn pixels(text: &str) -> Vec<Vec<bool>> {
+    let mut rows = vec![Vec::new(); GLYPH_HEIGHT];
+    for (index, symbol) in text.chars().enumerate() {
+        let glyph = glyph(symbol.to_ascii_uppercase()).unwrap_or_else(|| glyph('?').unwrap());
+        for (row, line) in rows.iter_mut().zip(glyph) {
+            if index > 0 {
+                row.push(false);
+            }
+            row.extend(line.chars().map(|pixel| pixel == '#'));
+        }
+    }
+    rows
+}
+
+fn scale_pixels(pixels: &[Vec<bool>], scale: u16) -> Vec<Vec<bool>> {
+    let scale = scale as usize;
+    pixels
+        .iter()
+        .flat_map(|row| {
+            let wide: Vec<bool> = row
+                .iter()
+                .flat_map(|pixel| std::iter::repeat_n(*pixel, scale))
+                .collect();
+            std::iter::repeat_n(wide, scale)
+        })
+        .collect()
+}
+
+fn block_rows(pixels: &[Vec<bool>]) -> Vec<Vec<char>> {
+    pixels
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|pixel| if *pixel { '█' } else { ' ' })
+                .collect()
+        })
+        .collect()
+}
+
+// Pack pairs of pixel rows into one row of cells
+fn half_block_rows(pixels: &[Vec<bool>]) -> Vec<Vec<char>> {
+    pixels
+        .chunks(2)
+        .map(|pair| {
+            let bottom = pair.get(1);
+            pair[0]
+                .iter()
+                .enumerate()
+                .map(|(column, top)| {
+                    let bottom = bottom.is_some_and(|row| row[column]);
+                    match (top, bottom) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Rows of a glyph of the built-in font, `#` marking the pixels that are drawn
+fn glyph(symbol: char) -> Option<[&'static str; GLYPH_HEIGHT]> {
+    let rows = match symbol {
+        'A' => [".###.", "#...#", "#####", "#...#", "#...#"],
+        'B' => ["####.", "#...#", "####.", "#...#", "####."],
+        'C' => [".####", "#....", "#....", "#....", ".####"],
+        'D' => ["####.", "#...#", "#...#", "#...#", "####."],
+        'E' => ["#####", "#....", "####.", "#....", "#####"],
+        'F' => ["#####", "#....", "####.", "#....", "#...."],
+        'G' => [".####", "#....", "#..##", "#...#", ".####"],
+        'H' => ["#...#", "#...#", "#####", "#...#", "#...#"],
+        'I' => ["###", ".#.", ".#.", ".#.", "###"],
+        'J' => ["..###", "...#.", "...#.", "#..#.", ".##.."],
+        'K' => ["#...#", "#..#.", "###..", "#..#.", "#...#"],
+        'L' => ["#....", "#....", "#....", "#....", "#####"],
+        'M' => ["#...#", "##.##", "#.#.#", "#...#", "#...#"],
+        'N' => ["#...#", "##..#", "#.#.#", "#..##", "#...#"],
+        'O' => [".###.", "#...#", "#...#", "#...#", ".###."],
+        'P' => ["####.", "#...#", "####.", "#....", "#...."],
+        'Q' => [".###.", "#...#", "#.#.#", "#..#.", ".##.#"],
+        'R' => ["####.", "#...#", "####.", "#..#.", "#...#"],
+        'S' => [".####", "#....", ".###.", "....#", "####."],
+        'T' => ["#####", "..#..", "..#..", "..#..", "..#.."],
+        'U' => ["#...#", "#...#", "#...#", "#...#", ".###."],
+        'V' => ["#...#", "#...#", "#...#", ".#.#.", "..#.."],
+        'W' => ["#...#", "#...#", "#.#.#", "##.##", "#...#"],
+        'X' => ["#...#", ".#.#.", "..#..", ".#.#.", "#...#"],
+        'Y' => ["#...#", ".#.#.", "..#..", "..#..", "..#.."],
+        'Z' => ["#####", "...#.", "..#..", ".#...", "#####"],
+        '0' => [".###.", "#..##", "#.#.#", "##..#", ".###."],
+        '1' => [".#.", "##.", ".#.", ".#.", "###"],
+        '2' => ["####.", "....#", ".###.", "#....", "#####"],
+        '3' => ["####.", "....#", ".###.", "....#", "####."],
+        '4' => ["#...#", "#...#", "#####", "....#", "....#"],
+        '5' => ["#####", "#....", "####.", "....#", "####."],
+        '6' => [".###.", "#....", "####.", "#...#", ".###."],
+        '7' => ["#####", "....#", "...#.", "..#..", "..#.."],
+        '8' => [".###.", "#...#", ".###.", "#...#", ".###."],
+        '9' => [".###.", "#...#", ".####", "....#", ".###."],
+        ' ' => ["...", "...", "...", "...", "..."],
+        '!' => ["#", "#", "#", ".", "#"],
+        '?' => ["###.", "...#", ".##.", "....", ".#.."],
+        '.' => [".", ".", ".", ".", "#"],
+        ',' => ["..", "..", "..", ".#", "#."],
+        ':' => [".", "#", ".", "#", "."],
+        ';' => ["..", ".#", "..", ".#", "#."],
+        '\'' => ["#", "#", ".", ".", "."],
+        '"' => ["#.#", "#.#", "...", "...", "..."],
+        '-' => ["...", "...", "###", "...", "..."],
+        '+' => ["...", ".#.", "###", ".#.", "..."],
+        '=' => ["...", "###", "...", "###", "..."],
+        '*' => ["#.#", ".#.", "#.#", "...", "..."],
+        '_' => ["....", "....", "....", "....", "####"],
+        '/' => ["....#", "...#.", "..#..", ".#...", "#...."],
+        '\\' => ["#....", ".#...", "..#..", "...#.", "....#"],
+        '(' => [".#", "#.", "#.", "#.", ".#"],
+        ')' => ["#.", ".#", ".#", ".#", "#."],
+        '[' => ["##", "#.", "#.", "#.", "##"],
+        ']' => ["##", ".#", ".#", ".#", "##"],
+        '<' => ["..#", ".#.", "#..", ".#.", "..#"],
+        '>' => ["#..", ".#.", "..#", ".#.", "#.."],
+        '#' => [".#.#.", "#####", ".#.#.", "#####", ".#.#."],
+        '%' => ["#...#", "...#.", "..#..", ".#...", "#...#"],
+        '&' => [".##..", "#..#.", ".##.#", "#..#.", ".##.#"],
+        _ => return None,
+    };
+    Some(rows)
+}
@@ -1,41 +1,309 @@
-use std::io::{self, Write};
+use std::fmt;
+use std::io;
 
-use crossterm::{
-    cursor, queue,
-    style::{self},
-};
+use serde::{Deserialize, Serialize};
 
-use crate::HexColor;
+use crate::backend;
+use crate::buffer::Buffer;
+use crate::font::FontStyle;
+use crate::gradient::{self, Gradient};
+use crate::layout::Rect;
+use crate::palette::Palette;
+use crate::symbols::{self, BorderSet, LineJoin, LineWeight};
+use crate::{Color, HexColor};
 
 // Define an enumeration of different border types
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum BorderType {
     Solid,
     Dotted,
     Dashed,
     Double,
+    Rounded,
+    Thick,
+    Ascii,
+    Block,
+    QuadrantInside,
+    QuadrantOutside,
+    None,              // Nothing is drawn, the space stays reserved
+    Custom(BorderSet), // Glyphs supplied by the user
+}
+
+impl BorderType {
+    pub fn border_set(self) -> BorderSet {
+        match self {
+            BorderType::Solid => symbols::SOLID,
+            BorderType::Dotted => symbols::DOTTED,
+            BorderType::Dashed => symbols::DASHED,
+            BorderType::Double => symbols::DOUBLE,
+            BorderType::Rounded => symbols::ROUNDED,
+            BorderType::Thick => symbols::THICK,
+            BorderType::Ascii => symbols::ASCII,
+            BorderType::Block => symbols::BLOCK,
+            BorderType::QuadrantInside => symbols::QUADRANT_INSIDE,
+            BorderType::QuadrantOutside => symbols::QUADRANT_OUTSIDE,
+            BorderType::None => symbols::NONE,
+            BorderType::Custom(set) => set,
+        }
+    }
+
+    // Weight of the lines drawn by the type, if it draws box-drawing lines at all
+    pub fn line_weight(self) -> Option<LineWeight> {
+        match self {
+            BorderType::Solid | BorderType::Dotted | BorderType::Dashed | BorderType::Rounded => {
+                Some(LineWeight::Light)
+            }
+            BorderType::Thick => Some(LineWeight::Heavy),
+            BorderType::Double => Some(LineWeight::Double),
+            _ => None,
+        }
+    }
+}
+
+// Define how colors are spread over the border
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BorderColoring {
+    #[default]
+    Layers, // One color per layer, taken from the border colors
+    Perimeter(Gradient), // Gradient running clockwise around each layer from the top left corner
+    Across(Gradient),    // Gradient running from the outermost to the innermost layer
+}
+
+// Define the time-based effects that advance with every tick of the event loop
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum BorderAnimation {
+    // Rotates the colors every tick by `speed` cells along a perimeter gradient, or by `speed`
+    // layers otherwise; a negative speed runs backwards
+    Marquee { speed: f64 },
+    Pulse { period: u32 }, // Fades the colors out and back in every `period` ticks
+    Rainbow { speed: f64 }, // Cycles every hue around the border, `speed` degrees per tick
+}
+
+// Define the shadow cast by a border onto the cells below and to the right of it
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Shadow {
+    pub offset_x: u16,    // Columns the shadow is shifted to the right
+    pub offset_y: u16,    // Rows the shadow is shifted down
+    pub fill: ShadowFill, // How the shadow cells are drawn
+}
+
+// Define how the cells covered by a shadow are drawn
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ShadowFill {
+    Glyph { symbol: char, color: Color }, // Overwrite the cells, e.g. with `░` or `▒`
+    // Keep the cells and scale their colors by the given brightness, default colors included.
+    // Below 1.0, blank cells whose background is already black get a `░` so the shadow shows
+    Darken(f64),
+}
+
+// Define the 3D effect given to a border by lighting two of its sides and shading the others
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bevel {
+    Raised, // Light top and left sides, dark bottom and right sides
+    Sunken, // Dark top and left sides, light bottom and right sides
+}
+
+// Define the four sides of a border
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Top, Side::Right, Side::Bottom, Side::Left];
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, Side::Top | Side::Bottom)
+    }
+}
+
+// Define how a single side of the border differs from the rest of the border
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SideStyle {
+    enabled: bool,                   // Indicates if the side is drawn
+    border_type: Option<BorderType>, // Type of the side, defaults to the border type
+    color: Option<Color>, // Color of every layer of the side, defaults to the layer colors
+    width: Option<usize>, // Number of layers of the side, defaults to the border width
+}
+
+impl Default for SideStyle {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            border_type: None,
+            color: None,
+            width: None,
+        }
+    }
+}
+
+// Define the style of each side of the border
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sides {
+    top: SideStyle,
+    right: SideStyle,
+    bottom: SideStyle,
+    left: SideStyle,
+}
+
+impl Sides {
+    fn get(&self, side: Side) -> &SideStyle {
+        match side {
+            Side::Top => &self.top,
+            Side::Right => &self.right,
+            Side::Bottom => &self.bottom,
+            Side::Left => &self.left,
+        }
+    }
+
+    fn get_mut(&mut self, side: Side) -> &mut SideStyle {
+        match side {
+            Side::Top => &mut self.top,
+            Side::Right => &mut self.right,
+            Side::Bottom => &mut self.bottom,
+            Side::Left => &mut self.left,
+        }
+    }
 }
 
 // Define the properties and structure of a border
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Border {
-    visible: bool,                    // Indicates if the border is visible
-    padding: usize,                   // Padding between the edge and the 1st border layer
-    width: usize,                     // Width of the border
-    color: HexColor,                  // Color of the border
-    border_type: BorderType,          // Type of the border
+    visible: bool,             // Indicates if the border is visible
+    release_when_hidden: bool, // Give the space of the layers to the content while hidden
+    margin: usize,             // Space between the edge of the area and the 1st border layer
+    padding: usize,            // Space between the innermost border layer and the content
+    width: usize,              // Width of the border
+    color: Color,              // Color of the border
+    border_type: BorderType,   // Type of the border
+    #[serde(default)]
     decoration_lines: DecorationLine, // Decoration lines for rendering the border
-    border_colors: Vec<HexColor>,     // Store multiple colors for different border layers
+    border_colors: Vec<Color>, // Store multiple colors for different border layers
+    ascii_fallback: bool,      // Draw with ASCII glyphs when the terminal lacks box-drawing support
+    sides: Sides,              // Per-side overrides of the border settings
+    coloring: BorderColoring,  // How colors are spread over the border
+    animation: Option<BorderAnimation>, // Effect applied on top of the coloring
+    shadow: Option<Shadow>,    // Shadow cast next to the border
+    bevel: Option<Bevel>,      // Light and dark variants of the colors on opposite sides
+    #[serde(skip)]
+    tick: u64, // Ticks elapsed since the border was created
+}
+
+// Define the reasons a border cannot be built
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BorderError {
+    ZeroWidth, // The border has no layers
+    TooManyColors {
+        colors: usize,
+        width: usize,
+    }, // More layer colors than layers
+    CharCountMismatch {
+        key: &'static str, // Decoration line with the wrong number of characters
+        chars: usize,
+        width: usize,
+    },
+    LayerCountMismatch {
+        key: &'static str, // List of per-layer settings with the wrong number of entries
+        count: usize,
+        width: usize,
+    },
+    EmptyPattern {
+        key: &'static str,
+    }, // A layer pattern without any character
+    EmptyGradient,           // A gradient was given without any color
+    ZeroPulsePeriod,         // A pulse animation would never advance
+    InvalidShadowBrightness, // A darkening shadow outside of 0.0 to 1.0
+}
+
+impl BorderError {
+    // Name of the setting the error is about, as it appears in configuration files
+    pub fn key(&self) -> &'static str {
+        match self {
+            BorderError::ZeroWidth => "width",
+            BorderError::TooManyColors { .. } => "border_colors",
+            BorderError::CharCountMismatch { key, .. }
+            | BorderError::LayerCountMismatch { key, .. }
+            | BorderError::EmptyPattern { key } => key,
+            BorderError::EmptyGradient => "coloring",
+            BorderError::ZeroPulsePeriod => "animation.period",
+            BorderError::InvalidShadowBrightness => "shadow.fill",
+        }
+    }
+}
+
+impl fmt::Display for BorderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BorderError::ZeroWidth => write!(f, "border width cannot be 0"),
+            BorderError::TooManyColors { colors, width } => write!(
+                f,
+                "{} colors provided for a border of width {}",
+                colors, width
+            ),
+            BorderError::CharCountMismatch { chars, width, .. } => write!(
+                f,
+                "{} characters provided for a border of width {}",
+                chars, width
+            ),
+            BorderError::LayerCountMismatch { count, width, .. } => write!(
+                f,
+                "{} layers provided for a border of width {}",
+                count, width
+            ),
+            BorderError::EmptyPattern { .. } => write!(f, "pattern needs at least one character"),
+            BorderError::EmptyGradient => write!(f, "gradient needs at least one color"),
+            BorderError::ZeroPulsePeriod => write!(f, "pulse period cannot be 0"),
+            BorderError::InvalidShadowBrightness => {
+                write!(f, "shadow brightness must be between 0.0 and 1.0")
+            }
+        }
+    }
 }
 
+impl std::error::Error for BorderError {}
+
 // Define the decoration lines for rendering the border
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct DecorationLine {
-    omni_char: char,                     // Character used to render the border
+    omni_char: char, // Character used for the whole border when set, '\0' otherwise
+    // Each vector holds one character per layer; layers without one use the border type's glyph
     vertical_char: Vec<char>,            // Vertical character for rendering
     horizontal_char: Vec<char>,          // Horizontal character for rendering
     top_right_corner_char: Vec<char>,    // Character for the top right corner of the border
     top_left_corner_char: Vec<char>,     // Character for the top left corner of the border
     bottom_right_corner_char: Vec<char>, // Character for the bottom right corner of the border
     bottom_left_corner_char: Vec<char>,  // Character for the bottom left corner of the border
+    // Repeating patterns per layer, taking precedence over the edge characters
+    vertical_pattern: Vec<String>,   // Pattern read from top to bottom
+    horizontal_pattern: Vec<String>, // Pattern read from left to right
+    pattern_align: PatternAlign,     // Where each pattern starts, so corners line up
+    font_style: Vec<FontStyle>,      // Font style of each layer
+    background: Vec<Color>,          // Background color of each layer
+}
+
+// Define where an edge pattern starts repeating
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternAlign {
+    #[default]
+    Start, // Begins right after the first corner of the edge
+    End,       // Ends right before the last corner of the edge
+    Symmetric, // Mirrored around the middle, both corners see the start of the pattern
+    Perimeter, // Runs on around the corners as one continuous loop
 }
 
 // Implement the default values for the Border structure
@@ -43,380 +311,697 @@ impl Default for Border {
     fn default() -> Self {
         Self {
             visible: true,
+            release_when_hidden: false,
+            margin: 0,
             padding: 0,
             width: 1,
-            color: HexColor::new("#FFFFFF"), // Default color set to white
-            border_type: BorderType::Solid,  // Default border type set to solid
-            border_colors: vec![HexColor::new("#FFFFFF")],
-            decoration_lines: DecorationLine {
-                omni_char: '\0',
-                vertical_char: vec![Self::default_vertical_border_char(BorderType::Solid)],
-                horizontal_char: vec![Self::default_horizontal_border_char(BorderType::Solid)],
-                top_right_corner_char: vec![Self::default_top_right_corner_char(BorderType::Solid)],
-                top_left_corner_char: vec![Self::default_top_left_corner_char(BorderType::Solid)],
-                bottom_right_corner_char: vec![Self::default_bottom_right_corner_char(
-                    BorderType::Solid,
-                )],
-                bottom_left_corner_char: vec![Self::default_bottom_left_corner_char(
-                    BorderType::Solid,
-                )],
-            },
+            color: HexColor::WHITE.into(), // Default color set to white
+            border_type: BorderType::Solid, // Default border type set to solid
+            border_colors: vec![HexColor::WHITE.into()],
+            ascii_fallback: true,
+            sides: Sides::default(),
+            coloring: BorderColoring::Layers,
+            animation: None,
+            shadow: None,
+            bevel: None,
+            tick: 0,
+            decoration_lines: DecorationLine::default(),
         }
     }
 }
 
 impl Border {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> BorderBuilder {
-        BorderBuilder {
-            border: Border::default(),
+        BorderBuilder::new()
+    }
+
+    // Check the border settings, so that every border that gets built can be rendered
+    pub(crate) fn validate(&self) -> Result<(), BorderError> {
+        if self.width == 0 {
+            return Err(BorderError::ZeroWidth);
+        }
+
+        if self.border_colors.len() > self.width {
+            return Err(BorderError::TooManyColors {
+                colors: self.border_colors.len(),
+                width: self.width,
+            });
         }
+
+        match &self.coloring {
+            BorderColoring::Perimeter(gradient) | BorderColoring::Across(gradient)
+                if gradient.stops().is_empty() =>
+            {
+                return Err(BorderError::EmptyGradient);
+            }
+            _ => {}
+        }
+
+        if let Some(BorderAnimation::Pulse { period: 0 }) = self.animation {
+            return Err(BorderError::ZeroPulsePeriod);
+        }
+
+        if let Some(Shadow {
+            fill: ShadowFill::Darken(brightness),
+            ..
+        }) = self.shadow
+        {
+            if !(0.0..=1.0).contains(&brightness) {
+                return Err(BorderError::InvalidShadowBrightness);
+            }
+        }
+
+        let lines = &self.decoration_lines;
+        let layers = [
+            ("decoration_lines.vertical_char", &lines.vertical_char),
+            ("decoration_lines.horizontal_char", &lines.horizontal_char),
+            (
+                "decoration_lines.top_left_corner_char",
+                &lines.top_left_corner_char,
+            ),
+            (
+                "decoration_lines.top_right_corner_char",
+                &lines.top_right_corner_char,
+            ),
+            (
+                "decoration_lines.bottom_left_corner_char",
+                &lines.bottom_left_corner_char,
+            ),
+            (
+                "decoration_lines.bottom_right_corner_char",
+                &lines.bottom_right_corner_char,
+            ),
+        ];
+
+        let patterns = [
+            ("decoration_lines.vertical_pattern", &lines.vertical_pattern),
+            (
+                "decoration_lines.horizontal_pattern",
+                &lines.horizontal_pattern,
+            ),
+        ];
+        let layer_counts = [
+            ("decoration_lines.font_style", lines.font_style.len()),
+            ("decoration_lines.background", lines.background.len()),
+        ];
+
+        for (key, chars) in layers {
+            if !chars.is_empty() && chars.len() != self.width {
+                return Err(BorderError::CharCountMismatch {
+                    key,
+                    chars: chars.len(),
+                    width: self.width,
+                });
+            }
+        }
+
+        let counts = patterns
+            .iter()
+            .map(|(key, patterns)| (*key, patterns.len()))
+            .chain(layer_counts);
+        for (key, count) in counts {
+            if count != 0 && count != self.width {
+                return Err(BorderError::LayerCountMismatch {
+                    key,
+                    count,
+                    width: self.width,
+                });
+            }
+        }
+
+        for (key, patterns) in patterns {
+            if patterns.iter().any(String::is_empty) {
+                return Err(BorderError::EmptyPattern { key });
+            }
+        }
+        Ok(())
     }
 
-    fn get_border_color(&self, layer: usize) -> HexColor {
-        self.border_colors.get(layer).cloned().unwrap_or_else(|| {
+    fn get_border_color(&self, layer: usize) -> Color {
+        self.border_colors.get(layer).copied().unwrap_or_else(|| {
             self.border_colors
                 .last()
-                .cloned()
-                .unwrap_or_else(|| HexColor::new("#FFFFFF"))
+                .copied()
+                .unwrap_or_else(|| HexColor::WHITE.into())
         })
     }
 
-    fn default_top_right_corner_char(border_type: BorderType) -> char {
-        match border_type {
-            BorderType::Solid => '┐',
-            BorderType::Dotted => '┒',
-            BorderType::Dashed => '┎',
-            BorderType::Double => '╗',
-        }
-    }
-
-    fn default_top_left_corner_char(border_type: BorderType) -> char {
-        match border_type {
-            BorderType::Solid => '┌',
-            BorderType::Dotted => '┎',
-            BorderType::Dashed => '┍',
-            BorderType::Double => '╔',
+    // Number of layers drawn on a side, 0 when the side is disabled
+    fn side_width(&self, side: Side) -> usize {
+        let style = self.sides.get(side);
+        if style.enabled {
+            style.width.unwrap_or(self.width)
+        } else {
+            0
         }
     }
 
-    fn default_bottom_right_corner_char(border_type: BorderType) -> char {
-        match border_type {
-            BorderType::Solid => '┘',
-            BorderType::Dotted => '┚',
-            BorderType::Dashed => '┖',
-            BorderType::Double => '╝',
-        }
+    fn side_type(&self, side: Side) -> BorderType {
+        self.sides
+            .get(side)
+            .border_type
+            .unwrap_or_else(|| self.check_border_type())
     }
 
-    fn default_bottom_left_corner_char(border_type: BorderType) -> char {
-        match border_type {
-            BorderType::Solid => '└',
-            BorderType::Dotted => '┖',
-            BorderType::Dashed => '┕',
-            BorderType::Double => '╚',
-        }
+    // Advance the animation by one tick of the event loop
+    pub fn tick(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
     }
 
-    fn default_vertical_border_char(border_type: BorderType) -> char {
-        match border_type {
-            BorderType::Solid => '│',
-            BorderType::Dotted => '┆',
-            BorderType::Dashed => '┊',
-            BorderType::Double => '║',
-        }
+    // Replace the colors of the layers, e.g. when the theme changes
+    pub fn set_colors<C: Into<Color>>(&mut self, colors: Vec<C>) {
+        self.border_colors = colors.into_iter().map(Into::into).collect();
     }
 
-    fn default_horizontal_border_char(border_type: BorderType) -> char {
-        match border_type {
-            BorderType::Solid => '─',
-            BorderType::Dotted => '┄',
-            BorderType::Dashed => '┈',
-            BorderType::Double => '═',
-        }
+    pub fn is_animated(&self) -> bool {
+        self.animation.is_some()
     }
 
-    fn render_vertical_border(
+    // Distance of a cell from the top left corner of its layer, walking clockwise,
+    // along with the length of the whole layer
+    fn perimeter_position(
         &self,
-        handle: &mut io::StdoutLock,
-        y_axis: u16,
-        start_x: u16,
+        window_size: (usize, usize),
         layer: usize,
-    ) -> Result<(), io::Error> {
-        let border_char = self
-            .decoration_lines
-            .vertical_char
-            .get(layer)
-            .copied()
-            .unwrap_or_else(|| Self::default_vertical_border_char(self.border_type));
-
-        queue!(handle, cursor::MoveTo(start_x, y_axis))?;
-        queue!(
-            handle,
-            style::SetForegroundColor(self.get_border_color(layer).to_rgb())
-        )?;
-        queue!(handle, style::Print(border_char))?;
-        queue!(handle, style::SetForegroundColor(style::Color::Reset))?;
-        Ok(())
+        x_axis: usize,
+        y_axis: usize,
+    ) -> (f64, f64) {
+        let (width, height) = window_size;
+        let offset = self.margin + layer;
+        let (left, top) = (offset, offset);
+        let right = width.saturating_sub(offset + 1).max(left);
+        let bottom = height.saturating_sub(offset + 1).max(top);
+        let (across, down) = (right - left, bottom - top);
+
+        let position = if y_axis <= top {
+            x_axis.saturating_sub(left)
+        } else if x_axis >= right {
+            across + (y_axis - top)
+        } else if y_axis >= bottom {
+            across + down + right.saturating_sub(x_axis)
+        } else {
+            2 * across + down + bottom.saturating_sub(y_axis)
+        };
+        (position as f64, (2 * (across + down)).max(1) as f64)
     }
 
-    fn render_left_vertical_border(
+    // Color of a single border cell, combining the side, the coloring and the animation
+    fn cell_color(
         &self,
-        handle: &mut io::StdoutLock,
         window_size: (usize, usize),
-    ) -> Result<(), io::Error> {
-        let (_, height) = window_size;
-
-        for layer in 0..self.width {
-            let y_start = self.padding as u16 + layer as u16 + 1;
-            let y_end = height as u16 - self.padding as u16 - layer as u16 - 1;
-            let x_axis = self.padding as u16 + layer as u16;
-
-            if y_start >= y_end {
-                break;
+        side: Side,
+        layer: usize,
+        x_axis: usize,
+        y_axis: usize,
+    ) -> Color {
+        let shift = match self.animation {
+            Some(BorderAnimation::Marquee { speed }) => self.tick as f64 * speed,
+            _ => 0.0,
+        };
+
+        let color = match (&self.sides.get(side).color, &self.coloring) {
+            (Some(color), _) => *color,
+            (None, BorderColoring::Layers) if shift == 0.0 || self.border_colors.is_empty() => {
+                self.get_border_color(layer)
             }
-
-            for y_axis in y_start..y_end {
-                self.render_vertical_border(handle, y_axis, x_axis, layer)?;
+            (None, BorderColoring::Layers) => {
+                let count = self.border_colors.len();
+                let index = (layer as f64 + shift).rem_euclid(count as f64) as usize % count;
+                self.border_colors[index]
             }
-        }
-        Ok(())
-    }
+            (None, BorderColoring::Perimeter(gradient)) => {
+                let (position, length) =
+                    self.perimeter_position(window_size, layer, x_axis, y_axis);
+                gradient.at_cyclic((position + shift) / length).into()
+            }
+            (None, BorderColoring::Across(gradient)) => {
+                let layers = self.width.max(2) - 1;
+                if shift == 0.0 {
+                    gradient.at(layer as f64 / layers as f64).into()
+                } else {
+                    gradient
+                        .at_cyclic((layer as f64 + shift) / (layers + 1) as f64)
+                        .into()
+                }
+            }
+        };
 
-    fn render_right_vertical_border(
-        &self,
-        handle: &mut io::StdoutLock,
-        window_size: (usize, usize),
-    ) -> Result<(), io::Error> {
-        let (width, height) = window_size;
+        let color = match self.animation {
+            Some(BorderAnimation::Rainbow { speed }) => {
+                let (position, length) =
+                    self.perimeter_position(window_size, layer, x_axis, y_axis);
+                gradient::hue(position / length * 360.0 + self.tick as f64 * speed).into()
+            }
+            Some(BorderAnimation::Pulse { period }) => {
+                let phase = (self.tick % period.max(1) as u64) as f64 / period.max(1) as f64;
+                // Swing the brightness between 20% and 100%
+                let brightness = 0.6 + 0.4 * (phase * std::f64::consts::TAU).cos();
+                color.map_hex(|color| color.mix(&HexColor::BLACK, 1.0 - brightness))
+            }
+            _ => color,
+        };
 
-        for layer in 0..self.width {
-            let y_start = self.padding as u16 + layer as u16 + 1;
-            let y_end = height as u16 - self.padding as u16 - layer as u16 - 1;
-            let x_axis = width as u16 - self.padding as u16 - 1 - layer as u16;
+        self.bevel_color(side, color)
+    }
 
-            if y_start >= y_end {
-                break;
+    // Lighten or shade a color depending on which side of a bevelled border it is drawn on
+    fn bevel_color(&self, side: Side, color: Color) -> Color {
+        let lit = match (self.bevel, side) {
+            (None, _) => return color,
+            (Some(Bevel::Raised), Side::Top | Side::Left) => true,
+            (Some(Bevel::Sunken), Side::Bottom | Side::Right) => true,
+            _ => false,
+        };
+
+        color.map_hex(|color| {
+            if lit {
+                color.mix(&HexColor::WHITE, 0.4)
+            } else {
+                color.mix(&HexColor::BLACK, 0.4)
             }
+        })
+    }
 
-            for y_axis in y_start..y_end {
-                self.render_vertical_border(handle, y_axis, x_axis, layer)?;
-            }
-        }
-        Ok(())
+    fn use_ascii(&self) -> bool {
+        self.ascii_fallback && !symbols::supports_unicode()
     }
 
-    pub fn render_vertical_borders(&self, window_size: (usize, usize)) -> Result<(), io::Error> {
-        if self.width == 0 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Border width cannot be 0",
-            ));
+    // Glyphs used for the layers of a side without decoration characters of their own
+    fn border_set(&self, side: Side) -> BorderSet {
+        let set = self.side_type(side).border_set();
+        if self.use_ascii() {
+            set.to_ascii()
+        } else {
+            set
         }
+    }
 
-        if self.decoration_lines.omni_char != '\0' || self.decoration_lines.vertical_char.is_empty()
-        {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Appropriate vertical character not provided",
-            ));
+    fn layer_char(&self, chars: &[char], layer: usize, default_char: char) -> char {
+        let omni_char = self.decoration_lines.omni_char;
+        let layer_char = if omni_char != '\0' {
+            Some(&omni_char)
+        } else {
+            chars.get(layer)
+        };
+
+        match layer_char {
+            Some(&layer_char) if layer_char.is_ascii() || !self.use_ascii() => layer_char,
+            _ => default_char,
         }
+    }
 
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
+    // Pick the glyph where a horizontal and a vertical side meet, None if neither is drawn
+    fn corner_char(&self, horizontal: Side, vertical: Side) -> Option<char> {
+        let top = horizontal == Side::Top;
+        let left = vertical == Side::Left;
+        let pick = |set: BorderSet| match (top, left) {
+            (true, true) => set.top_left,
+            (true, false) => set.top_right,
+            (false, true) => set.bottom_left,
+            (false, false) => set.bottom_right,
+        };
+
+        let horizontal_type = self.side_type(horizontal);
+        let vertical_type = self.side_type(vertical);
+        match (horizontal_type, vertical_type) {
+            (BorderType::None, BorderType::None) => return None,
+            // Only one side is drawn, so it runs straight through the corner
+            (BorderType::None, _) => {
+                let set = self.border_set(vertical);
+                return Some(if left { set.left } else { set.right });
+            }
+            (_, BorderType::None) => {
+                let set = self.border_set(horizontal);
+                return Some(if top { set.top } else { set.bottom });
+            }
+            _ if horizontal_type == vertical_type => {
+                return Some(pick(self.border_set(horizontal)))
+            }
+            _ => {}
+        }
 
-        self.render_left_vertical_border(&mut handle, window_size)?;
-        self.render_right_vertical_border(&mut handle, window_size)?;
+        // Different line types meet: join them with the matching mixed glyph
+        let joined = match (horizontal_type.line_weight(), vertical_type.line_weight()) {
+            (Some(horizontal_weight), Some(vertical_weight)) => {
+                let mut join = LineJoin::default();
+                if top {
+                    join.down = vertical_weight;
+                } else {
+                    join.up = vertical_weight;
+                }
+                if left {
+                    join.right = horizontal_weight;
+                } else {
+                    join.left = horizontal_weight;
+                }
+                join.glyph()
+            }
+            _ => None,
+        };
 
-        handle.flush()?;
-        Ok(())
+        match joined {
+            Some(glyph) if !self.use_ascii() => Some(glyph),
+            _ => Some(pick(self.border_set(horizontal))),
+        }
     }
 
-    fn render_top_horizontal_border(
+    // Character of an edge pattern for a cell `position` cells into an edge of `length` cells
+    fn pattern_char(
         &self,
-        handle: &mut io::StdoutLock,
-        window_size: (usize, usize),
-    ) -> Result<(), io::Error> {
-        let (width, _) = window_size;
-
-        for layer in 0..self.width {
-            let x_start = self.padding as u16 + layer as u16 + 1;
-            let x_end = width as u16 - self.padding as u16 - layer as u16 - 1;
-            let y_axis = self.padding as u16 + layer as u16;
+        pattern: &str,
+        position: usize,
+        length: usize,
+        perimeter: usize,
+    ) -> char {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let count = pattern.len();
+        let index = match self.decoration_lines.pattern_align {
+            PatternAlign::Start => position % count,
+            PatternAlign::End => (count - (length - position) % count) % count,
+            PatternAlign::Symmetric if position * 2 >= length => (length - 1 - position) % count,
+            PatternAlign::Symmetric => position % count,
+            PatternAlign::Perimeter => perimeter % count,
+        };
+        pattern[index]
+    }
 
-            if x_start >= x_end {
-                break;
-            }
+    fn render_cell(
+        &self,
+        buffer: &mut Buffer,
+        area: Rect,
+        (x_axis, y_axis): (usize, usize),
+        layer: usize,
+        border_char: char,
+        color: &Color,
+    ) {
+        let (x_axis, y_axis) = (area.x + x_axis as u16, area.y + y_axis as u16);
+        if !self.should_render_border(area, x_axis, y_axis) {
+            return;
+        }
 
-            for x_axis in x_start..x_end {
-                self.render_horizontal_border(handle, x_axis, y_axis, layer)?;
+        buffer.set_line(x_axis, y_axis, border_char, color);
+        if let Some(cell) = buffer.get_mut(x_axis, y_axis) {
+            let lines = &self.decoration_lines;
+            cell.style = lines.font_style.get(layer).copied().unwrap_or_default();
+            // Layers without a background of their own show the one below them
+            if let Some(background) = lines.background.get(layer) {
+                cell.bg = Some(*background);
             }
         }
-        Ok(())
     }
 
-    fn render_bottom_horizontal_border(
-        &self,
-        handle: &mut io::StdoutLock,
-        window_size: (usize, usize),
-    ) -> Result<(), io::Error> {
-        let (width, height) = window_size;
-
-        for layer in 0..self.width {
-            let x_start = self.padding as u16 + layer as u16 + 1;
-            let x_end = width as u16 - self.padding as u16 - layer as u16 - 1;
-            let y_axis = height as u16 - self.padding as u16 - 1 - layer as u16;
+    // Draw every layer of one side, running between the layers of the two adjacent sides
+    fn render_side(&self, buffer: &mut Buffer, area: Rect, side: Side) {
+        if self.side_type(side) == BorderType::None {
+            return;
+        }
 
-            if x_start >= x_end {
+        let window_size = (area.width as usize, area.height as usize);
+        let (width, height) = window_size;
+        let set = self.border_set(side);
+        let lines = &self.decoration_lines;
+        let (default_char, before, after) = match side {
+            Side::Top => (set.top, Side::Left, Side::Right),
+            Side::Bottom => (set.bottom, Side::Left, Side::Right),
+            Side::Left => (set.left, Side::Top, Side::Bottom),
+            Side::Right => (set.right, Side::Top, Side::Bottom),
+        };
+        let (chars, patterns) = if side.is_horizontal() {
+            (&lines.horizontal_char, &lines.horizontal_pattern)
+        } else {
+            (&lines.vertical_char, &lines.vertical_pattern)
+        };
+        let (length, depth) = if side.is_horizontal() {
+            (width, height)
+        } else {
+            (height, width)
+        };
+
+        for layer in 0..self.side_width(side) {
+            // A layer stops at the matching layer of the adjacent side, or just inside its
+            // innermost layer when the adjacent side is thinner
+            let start = self.margin + (layer + 1).min(self.side_width(before));
+            let end = length.saturating_sub(self.margin + (layer + 1).min(self.side_width(after)));
+            let offset = self.margin + layer;
+
+            if start >= end || offset >= depth {
                 break;
             }
 
-            for x_axis in x_start..x_end {
-                self.render_horizontal_border(handle, x_axis, y_axis, layer)?;
+            let fixed = match side {
+                Side::Top | Side::Left => offset,
+                Side::Bottom | Side::Right => depth - 1 - offset,
+            };
+            let layer_char = self.layer_char(chars, layer, default_char);
+            // The omni character overrides patterns like it overrides the edge characters
+            let pattern = patterns.get(layer).filter(|_| lines.omni_char == '\0');
+
+            for position in start..end {
+                let (x_axis, y_axis) = if side.is_horizontal() {
+                    (position, fixed)
+                } else {
+                    (fixed, position)
+                };
+                let border_char = match pattern {
+                    Some(pattern) => {
+                        let (along, _) =
+                            self.perimeter_position(window_size, layer, x_axis, y_axis);
+                        let border_char = self.pattern_char(
+                            pattern,
+                            position - start,
+                            end - start,
+                            along as usize,
+                        );
+                        self.layer_char(&[border_char], 0, default_char)
+                    }
+                    None => layer_char,
+                };
+                let color = self.cell_color(window_size, side, layer, x_axis, y_axis);
+                self.render_cell(buffer, area, (x_axis, y_axis), layer, border_char, &color);
             }
         }
-        Ok(())
     }
 
-    pub fn render_horizontal_borders(&self, window_size: (usize, usize)) -> Result<(), io::Error> {
-        if self.width == 0 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Border width cannot be 0",
-            ));
+    fn render_corners(&self, buffer: &mut Buffer, area: Rect) {
+        let window_size = (area.width as usize, area.height as usize);
+        let (width, height) = window_size;
+        let lines = &self.decoration_lines;
+        let corners = [
+            (Side::Top, Side::Left, &lines.top_left_corner_char),
+            (Side::Top, Side::Right, &lines.top_right_corner_char),
+            (Side::Bottom, Side::Left, &lines.bottom_left_corner_char),
+            (Side::Bottom, Side::Right, &lines.bottom_right_corner_char),
+        ];
+
+        for (horizontal, vertical, chars) in corners {
+            let Some(default_char) = self.corner_char(horizontal, vertical) else {
+                continue;
+            };
+            // Take the color from the side that is actually drawn through the corner
+            let color_side = if self.side_type(horizontal) == BorderType::None {
+                vertical
+            } else {
+                horizontal
+            };
+            let layers = self.side_width(horizontal).min(self.side_width(vertical));
+
+            for layer in 0..layers {
+                let offset = self.margin + layer;
+                if offset >= width || offset >= height {
+                    break;
+                }
+
+                let x_axis = if vertical == Side::Left {
+                    offset
+                } else {
+                    width - 1 - offset
+                };
+                let y_axis = if horizontal == Side::Top {
+                    offset
+                } else {
+                    height - 1 - offset
+                };
+                let border_char = self.layer_char(chars, layer, default_char);
+                let color = self.cell_color(window_size, color_side, layer, x_axis, y_axis);
+
+                self.render_cell(buffer, area, (x_axis, y_axis), layer, border_char, &color);
+            }
         }
+    }
 
-        if self.decoration_lines.omni_char != '\0'
-            || self.decoration_lines.horizontal_char.is_empty()
-        {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Appropriate horizontal character not provided",
-            ));
-        }
+    // Borders coming from the builder are valid, but deserialized ones may not be
+    fn check_renderable(&self) -> Result<(), io::Error> {
+        self.validate()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+    }
 
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
+    // Draw the border into the buffer; glyphs already in the buffer are joined with the
+    // border's lines, so borders of neighbouring containers merge into junctions
+    pub fn render(&self, buffer: &mut Buffer, area: Rect) -> Result<(), io::Error> {
+        self.check_renderable()?;
 
-        self.render_top_horizontal_border(&mut handle, window_size)?;
-        self.render_bottom_horizontal_border(&mut handle, window_size)?;
+        if !self.visible {
+            return Ok(());
+        }
 
-        handle.flush()?;
+        self.render_shadow(buffer, area);
+        for side in Side::ALL {
+            self.render_side(buffer, area, side);
+        }
+        self.render_corners(buffer, area);
         Ok(())
     }
 
-    fn render_corner(
-        &self,
-        handle: &mut io::StdoutLock,
-        x_axis: u16,
-        y_axis: u16,
-        corner_char: char,
-        layer: usize,
-    ) -> Result<(), io::Error> {
-        queue!(handle, cursor::MoveTo(x_axis, y_axis))?;
-        queue!(
-            handle,
-            style::SetForegroundColor(self.get_border_color(layer).to_rgb())
-        )?;
-        queue!(handle, style::Print(corner_char))?;
-        queue!(handle, style::SetForegroundColor(style::Color::Reset))?;
-        Ok(())
+    // Draw the shadow over the cells the offset border rectangle covers outside of the border
+    fn render_shadow(&self, buffer: &mut Buffer, area: Rect) {
+        let Some(shadow) = &self.shadow else {
+            return;
+        };
+
+        let border_rect = self.border_rect(area);
+        let shadow_rect = Rect::new(
+            border_rect.x.saturating_add(shadow.offset_x),
+            border_rect.y.saturating_add(shadow.offset_y),
+            border_rect.width,
+            border_rect.height,
+        );
+        let palette = Palette::current();
+
+        for y_axis in shadow_rect.y..shadow_rect.y.saturating_add(shadow_rect.height) {
+            for x_axis in shadow_rect.x..shadow_rect.x.saturating_add(shadow_rect.width) {
+                if border_rect.contains(x_axis, y_axis) {
+                    continue;
+                }
+
+                match &shadow.fill {
+                    ShadowFill::Glyph { symbol, color } => {
+                        buffer.set_char(x_axis, y_axis, *symbol, color)
+                    }
+                    ShadowFill::Darken(brightness) => {
+                        if let Some(cell) = buffer.get_mut(x_axis, y_axis) {
+                            // Cells in the terminal's default colors are darkened from the
+                            // colors the palette reports for them
+                            let resolve = |color: Option<Color>, default: HexColor| {
+                                color
+                                    .and_then(|color| color.resolve(&palette))
+                                    .unwrap_or(default)
+                            };
+                            let foreground = resolve(cell.fg, palette.foreground);
+                            let background = resolve(cell.bg, palette.background);
+                            // A black background cannot get any darker, so the shadow shows
+                            // as a shade glyph on the blank cells there instead
+                            if *brightness < 1.0
+                                && background == HexColor::BLACK
+                                && cell.symbol == " "
+                            {
+                                cell.symbol = String::from('░');
+                            }
+                            cell.fg = Some(Color::Rgb(
+                                foreground.mix(&HexColor::BLACK, 1.0 - brightness),
+                            ));
+                            cell.bg = Some(Color::Rgb(
+                                background.mix(&HexColor::BLACK, 1.0 - brightness),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
     }
 
-    fn render_corners(&self, window_size: (usize, usize)) -> Result<(), io::Error> {
-        let (width, height) = window_size;
+    pub fn render_vertical_borders(&self, window_size: (usize, usize)) -> Result<(), io::Error> {
+        self.check_renderable()?;
 
-        let stdout = io::stdout();
-        let mut handle = stdout.lock();
+        let area = window_area(window_size);
+        let mut buffer = Buffer::new(area);
+        self.render_side(&mut buffer, area, Side::Left);
+        self.render_side(&mut buffer, area, Side::Right);
 
-        for layer in 0..self.width {
-            let top_y = self.padding as u16 + layer as u16;
-            let bottom_y = height as u16 - self.padding as u16 - 1 - layer as u16;
-            let left_x = self.padding as u16 + layer as u16;
-            let right_x = width as u16 - self.padding as u16 - 1 - layer as u16;
+        buffer.flush(backend::stdout().as_mut())
+    }
 
-            if top_y >= height as u16
-                || bottom_y >= height as u16
-                || left_x >= width as u16
-                || right_x >= width as u16
-            {
-                break;
-            }
+    pub fn render_horizontal_borders(&self, window_size: (usize, usize)) -> Result<(), io::Error> {
+        self.check_renderable()?;
 
-            let top_left_char = self
-                .decoration_lines
-                .top_left_corner_char
-                .get(layer)
-                .copied()
-                .unwrap_or_else(|| Self::default_top_left_corner_char(self.border_type));
-            let top_right_char = self
-                .decoration_lines
-                .top_right_corner_char
-                .get(layer)
-                .copied()
-                .unwrap_or_else(|| Self::default_top_right_corner_char(self.border_type));
-            let bottom_left_char = self
-                .decoration_lines
-                .bottom_left_corner_char
-                .get(layer)
-                .copied()
-                .unwrap_or_else(|| Self::default_bottom_left_corner_char(self.border_type));
-            let bottom_right_char = self
-                .decoration_lines
-                .bottom_right_corner_char
-                .get(layer)
-                .copied()
-                .unwrap_or_else(|| Self::default_bottom_right_corner_char(self.border_type));
+        let area = window_area(window_size);
+        let mut buffer = Buffer::new(area);
+        self.render_side(&mut buffer, area, Side::Top);
+        self.render_side(&mut buffer, area, Side::Bottom);
+
+        buffer.flush(backend::stdout().as_mut())
+    }
+
+    pub fn render_box(&self, window_size: (usize, usize)) -> Result<(), io::Error> {
+        let area = window_area(window_size);
+        let mut buffer = Buffer::new(area);
+        self.render(&mut buffer, area)?;
+
+        buffer.flush(backend::stdout().as_mut())
+    }
 
-            self.render_corner(&mut handle, left_x, top_y, top_left_char, layer)?;
-            self.render_corner(&mut handle, right_x, top_y, top_right_char, layer)?;
-            self.render_corner(&mut handle, left_x, bottom_y, bottom_left_char, layer)?;
-            self.render_corner(&mut handle, right_x, bottom_y, bottom_right_char, layer)?;
+    // Layers a side takes up in the box model, which hidden borders may give up
+    fn reserved_width(&self, side: Side) -> usize {
+        if !self.visible && self.release_when_hidden {
+            0
+        } else {
+            self.side_width(side)
         }
+    }
 
-        handle.flush()?;
-        Ok(())
+    // Area the border layers are drawn in: the given area without the margin
+    pub fn border_rect(&self, area: Rect) -> Rect {
+        area.shrink(self.margin, self.margin, self.margin, self.margin)
     }
 
-    pub fn render_box(&self, window_size: (usize, usize)) -> Result<(), io::Error> {
-        self.render_vertical_borders(window_size)?;
-        self.render_horizontal_borders(window_size)?;
-        self.render_corners(window_size)?;
-        Ok(())
+    // Area inside the innermost border layer, padding included
+    fn padding_rect(&self, area: Rect) -> Rect {
+        self.border_rect(area).shrink(
+            self.reserved_width(Side::Left),
+            self.reserved_width(Side::Top),
+            self.reserved_width(Side::Right),
+            self.reserved_width(Side::Bottom),
+        )
     }
 
-    fn render_horizontal_border(
-        &self,
-        handle: &mut io::StdoutLock,
-        x_axis: u16,
-        start_y: u16,
-        layer: usize,
-    ) -> Result<(), io::Error> {
-        let border_char = self
-            .decoration_lines
-            .horizontal_char
-            .get(layer)
-            .copied()
-            .unwrap_or_else(|| Self::default_horizontal_border_char(self.border_type));
-
-        queue!(handle, cursor::MoveTo(x_axis, start_y))?;
-        queue!(
-            handle,
-            style::SetForegroundColor(self.get_border_color(layer).to_rgb())
-        )?;
-        queue!(handle, style::Print(border_char))?;
-        queue!(handle, style::SetForegroundColor(style::Color::Reset))?;
-        Ok(())
+    // Area left for the content once the margin, the border layers and the padding are taken off
+    pub fn inner_rect(&self, area: Rect) -> Rect {
+        self.padding_rect(area)
+            .shrink(self.padding, self.padding, self.padding, self.padding)
+    }
+
+    // Area needed to fit content of the given size, the inverse of `inner_rect` as long as the
+    // frame fits between the content and the edges of the coordinate space
+    pub fn outer_rect(&self, content: Rect) -> Rect {
+        let grow = |side: Side| self.margin + self.reserved_width(side) + self.padding;
+        content.grow(
+            grow(Side::Left),
+            grow(Side::Top),
+            grow(Side::Right),
+            grow(Side::Bottom),
+        )
+    }
+
+    // Columns and rows taken by the margin, the border layers and the padding together
+    pub fn frame_size(&self) -> (usize, usize) {
+        let frame = |side: Side| self.margin + self.reserved_width(side) + self.padding;
+        (
+            frame(Side::Left) + frame(Side::Right),
+            frame(Side::Top) + frame(Side::Bottom),
+        )
     }
 
-    fn check_current_position_is_padding(&self, x_axis: usize, y_axis: usize) -> bool {
-        (x_axis < self.padding)
-            || (y_axis < self.padding)
-            || (x_axis >= self.width - self.padding)
-            || (y_axis >= self.width - self.padding)
+    // Check whether an absolute position lies between the innermost border layer and the content
+    pub fn check_current_position_is_padding(&self, area: Rect, x_axis: u16, y_axis: u16) -> bool {
+        self.padding_rect(area).contains(x_axis, y_axis)
+            && !self.inner_rect(area).contains(x_axis, y_axis)
     }
 
-    fn should_render_border(&self, x_axis: usize, y_axis: usize) -> bool {
-        !self.check_current_position_is_padding(x_axis, y_axis) && self.visible
+    // Check whether an absolute position lies on one of the border layers
+    fn should_render_border(&self, area: Rect, x_axis: u16, y_axis: u16) -> bool {
+        self.visible
+            && self.border_rect(area).contains(x_axis, y_axis)
+            && !self.padding_rect(area).contains(x_axis, y_axis)
     }
 
     fn check_border_type(&self) -> BorderType {
@@ -426,82 +1011,195 @@ impl Border {
 
 pub struct BorderBuilder {
     border: Border,
+    border_char: Option<char>, // Edge character applied to every layer once the width is known
+}
+
+impl Default for BorderBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BorderBuilder {
     pub fn new() -> Self {
         BorderBuilder {
             border: Border::default(),
+            border_char: None,
         }
     }
 
+    pub fn margin(mut self, margin: usize) -> Self {
+        self.border.margin = margin;
+        self
+    }
+
     pub fn padding(mut self, padding: usize) -> Self {
         self.border.padding = padding;
         self
     }
 
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.border.visible = visible;
+        self
+    }
+
+    // Let the content take over the space of the border layers while the border is hidden
+    pub fn release_when_hidden(mut self, release_when_hidden: bool) -> Self {
+        self.border.release_when_hidden = release_when_hidden;
+        self
+    }
+
     pub fn width(mut self, width: usize) -> Self {
         self.border.width = width;
         self
     }
 
-    pub fn color(mut self, color: HexColor) -> Self {
-        self.border.color = color;
+    pub fn color(mut self, color: impl Into<Color>) -> Self {
+        self.border.color = color.into();
         self
     }
 
     pub fn border_type(mut self, border_type: BorderType) -> Self {
         self.border.border_type = border_type;
-
-        self.border.decoration_lines.vertical_char =
-            vec![Border::default_vertical_border_char(border_type); self.border.width];
-
-        self.border.decoration_lines.horizontal_char =
-            vec![Border::default_horizontal_border_char(border_type); self.border.width];
-
-        self.border.decoration_lines.top_left_corner_char =
-            vec![Border::default_top_left_corner_char(border_type); self.border.width];
-        self.border.decoration_lines.top_right_corner_char =
-            vec![Border::default_top_right_corner_char(border_type); self.border.width];
-        self.border.decoration_lines.bottom_left_corner_char =
-            vec![Border::default_bottom_left_corner_char(border_type); self.border.width];
-        self.border.decoration_lines.bottom_right_corner_char =
-            vec![Border::default_bottom_right_corner_char(border_type); self.border.width];
-
         self
     }
 
-    pub fn with_color(mut self, color: HexColor) -> Self {
-        self.border.border_colors = vec![color];
+    pub fn with_color(mut self, color: impl Into<Color>) -> Self {
+        self.border.border_colors = vec![color.into()];
         self
     }
 
-    pub fn with_colors(mut self, colors: Vec<HexColor>) -> Self {
-        if colors.len() > self.border.width {
-            panic!("Number of colors provided exceeds border width");
-        }
-        self.border.border_colors = colors;
+    // One color per layer, from the outermost inwards; at most one per layer
+    pub fn with_colors<C: Into<Color>>(mut self, colors: Vec<C>) -> Self {
+        self.border.border_colors = colors.into_iter().map(Into::into).collect();
         self
     }
 
+    // Draw the edges of every layer with one character, keeping the corners of the border type
     pub fn border_char(mut self, border_char: char) -> Self {
-        self.border.decoration_lines.vertical_char = vec![border_char];
+        self.border_char = Some(border_char);
+        self
+    }
+
+    // Draw the edges and the corners of every layer with one character
+    pub fn omni_char(mut self, omni_char: char) -> Self {
+        self.border.decoration_lines.omni_char = omni_char;
         self
     }
 
+    // One character per layer, from the outermost inwards; must match the border width
     pub fn vertical_border_char(mut self, chars: Vec<char>) -> Self {
-        // NOTE: Must be equal to the border width
         self.border.decoration_lines.vertical_char = chars;
         self
     }
 
+    // One character per layer, from the outermost inwards; must match the border width
     pub fn horizontal_border_char(mut self, chars: Vec<char>) -> Self {
-        // NOTE: Must be equal to the border width
         self.border.decoration_lines.horizontal_char = chars;
         self
     }
 
-    pub fn build(self) -> Border {
-        self.border
+    // One repeating pattern per layer, e.g. "─·", read from top to bottom
+    pub fn vertical_pattern(mut self, patterns: Vec<String>) -> Self {
+        self.border.decoration_lines.vertical_pattern = patterns;
+        self
+    }
+
+    // One repeating pattern per layer, e.g. "─·", read from left to right
+    pub fn horizontal_pattern(mut self, patterns: Vec<String>) -> Self {
+        self.border.decoration_lines.horizontal_pattern = patterns;
+        self
+    }
+
+    pub fn pattern_align(mut self, pattern_align: PatternAlign) -> Self {
+        self.border.decoration_lines.pattern_align = pattern_align;
+        self
+    }
+
+    // One font style per layer, from the outermost inwards
+    pub fn layer_styles(mut self, styles: Vec<FontStyle>) -> Self {
+        self.border.decoration_lines.font_style = styles;
+        self
+    }
+
+    // One background color per layer, from the outermost inwards
+    pub fn layer_backgrounds<C: Into<Color>>(mut self, colors: Vec<C>) -> Self {
+        self.border.decoration_lines.background = colors.into_iter().map(Into::into).collect();
+        self
+    }
+
+    // Draw only the given sides, e.g. `&[Side::Top]` for a single rule above the content
+    pub fn sides(mut self, sides: &[Side]) -> Self {
+        for side in Side::ALL {
+            self.border.sides.get_mut(side).enabled = sides.contains(&side);
+        }
+        self
+    }
+
+    pub fn side_type(mut self, side: Side, border_type: BorderType) -> Self {
+        self.border.sides.get_mut(side).border_type = Some(border_type);
+        self
+    }
+
+    pub fn side_color(mut self, side: Side, color: impl Into<Color>) -> Self {
+        self.border.sides.get_mut(side).color = Some(color.into());
+        self
+    }
+
+    pub fn side_width(mut self, side: Side, width: usize) -> Self {
+        self.border.sides.get_mut(side).width = Some(width);
+        self
+    }
+
+    // Spread a gradient clockwise around every layer of the border
+    pub fn gradient(mut self, gradient: Gradient) -> Self {
+        self.border.coloring = BorderColoring::Perimeter(gradient);
+        self
+    }
+
+    // Spread a gradient from the outermost to the innermost layer
+    pub fn layer_gradient(mut self, gradient: Gradient) -> Self {
+        self.border.coloring = BorderColoring::Across(gradient);
+        self
+    }
+
+    pub fn animation(mut self, animation: BorderAnimation) -> Self {
+        self.border.animation = Some(animation);
+        self
     }
+
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.border.shadow = Some(shadow);
+        self
+    }
+
+    pub fn bevel(mut self, bevel: Bevel) -> Self {
+        self.border.bevel = Some(bevel);
+        self
+    }
+
+    // Draw with ASCII glyphs when the terminal does not advertise UTF-8 support, on by default
+    pub fn ascii_fallback(mut self, ascii_fallback: bool) -> Self {
+        self.border.ascii_fallback = ascii_fallback;
+        self
+    }
+
+    pub fn build(mut self) -> Result<Border, BorderError> {
+        if let Some(border_char) = self.border_char {
+            let width = self.border.width;
+            let lines = &mut self.border.decoration_lines;
+            for chars in [&mut lines.vertical_char, &mut lines.horizontal_char] {
+                if chars.is_empty() {
+                    *chars = vec![border_char; width];
+                }
+            }
+        }
+
+        self.border.validate()?;
+        Ok(self.border)
+    }
+}
+
+fn window_area((width, height): (usize, usize)) -> Rect {
+    Rect::new(0, 0, width as u16, height as u16)
 }
@@ -0,0 +1,275 @@
+use std::io;
+use std::ops::Range;
+
+use crate::backend::Backend;
+use crate::font::FontStyle;
+use crate::layout::Rect;
+use crate::symbols::{self, LineJoin};
+use crate::Color;
+
+// Define a single terminal cell
+#[derive(Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: String, // Grapheme cluster shown, empty in the second cell of a wide one
+    pub fg: Option<Color>, // Foreground color, the terminal default when None
+    pub bg: Option<Color>, // Background color, the terminal default when None
+    pub style: FontStyle, // Modifiers, underline shape and underline color
+    pub link: Option<String>, // URL opened when the cell is clicked
+    lines: LineJoin,    // Box-drawing lines leaving the cell, used to merge borders
+    transparent: bool,  // Show the layers below instead of the cell when composed
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: String::from(" "),
+            fg: None,
+            bg: None,
+            style: FontStyle::default(),
+            link: None,
+            lines: LineJoin::default(),
+            transparent: false,
+        }
+    }
+}
+
+impl Cell {
+    // Blank cell through which the compositor shows the layers below, e.g. around the
+    // rounded corners of a popup. Drawing a symbol into the cell makes it opaque again
+    pub fn transparent() -> Self {
+        Self {
+            transparent: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.transparent
+    }
+
+    pub fn lines(&self) -> LineJoin {
+        self.lines
+    }
+
+    // Number of cells taken by the symbol, 0 in the second cell of a wide character
+    pub fn width(&self) -> usize {
+        if self.is_continuation() {
+            0
+        } else {
+            symbols::grapheme_width(&self.symbol)
+        }
+    }
+
+    // Whether the cell is covered by the wide character of the cell before it
+    pub fn is_continuation(&self) -> bool {
+        self.symbol.is_empty()
+    }
+
+    // Replace the symbol with a space, keeping the colors and style
+    fn blank(&mut self) {
+        self.symbol = String::from(" ");
+        self.lines = LineJoin::default();
+    }
+}
+
+// Define a grid of cells covering an area of the terminal, drawn into before being flushed
+pub struct Buffer {
+    area: Rect,
+    cells: Vec<Cell>,
+    drawn: Vec<bool>, // Cells written since the buffer was created, the only ones flushed
+}
+
+impl Buffer {
+    pub fn new(area: Rect) -> Self {
+        let size = area.width as usize * area.height as usize;
+        Self {
+            area,
+            cells: vec![Cell::default(); size],
+            drawn: vec![false; size],
+        }
+    }
+
+    // Copy of the buffer in which only the cells that differ from `previous` count as drawn,
+    // cells that went back to blank included, so flushing it updates a screen showing
+    // `previous`. Every cell counts when the two buffers cover different areas
+    pub fn diff(&self, previous: &Buffer) -> Buffer {
+        let drawn = if previous.area == self.area {
+            self.cells
+                .iter()
+                .zip(&previous.cells)
+                .map(|(cell, before)| cell != before)
+                .collect()
+        } else {
+            vec![true; self.cells.len()]
+        };
+        Buffer {
+            area: self.area,
+            cells: self.cells.clone(),
+            drawn,
+        }
+    }
+
+    // Count every cell as drawn, so flushing writes the whole area, blank cells included
+    pub fn touch(&mut self) {
+        self.drawn.fill(true);
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    fn index(&self, x_axis: u16, y_axis: u16) -> Option<usize> {
+        let area = self.area;
+        let inside = x_axis >= area.x
+            && y_axis >= area.y
+            && x_axis - area.x < area.width
+            && y_axis - area.y < area.height;
+
+        inside
+            .then(|| (y_axis - area.y) as usize * area.width as usize + (x_axis - area.x) as usize)
+    }
+
+    pub fn get(&self, x_axis: u16, y_axis: u16) -> Option<&Cell> {
+        self.index(x_axis, y_axis).map(|index| &self.cells[index])
+    }
+
+    // Cell to draw into, which is flushed from then on even when left blank
+    pub fn get_mut(&mut self, x_axis: u16, y_axis: u16) -> Option<&mut Cell> {
+        let index = self.index(x_axis, y_axis)?;
+        self.drawn[index] = true;
+        Some(&mut self.cells[index])
+    }
+
+    // Overwrite every cell of an area, e.g. to clear it or to make it transparent
+    pub fn fill(&mut self, area: Rect, cell: &Cell) {
+        let area = area.intersection(self.area);
+        for y_axis in area.y..area.y + area.height {
+            for x_axis in area.x..area.x + area.width {
+                if let Some(target) = self.get_mut(x_axis, y_axis) {
+                    *target = cell.clone();
+                }
+            }
+        }
+    }
+
+    // Overwrite a cell; positions outside the buffer are ignored
+    pub fn set_char(&mut self, x_axis: u16, y_axis: u16, symbol: char, fg: impl Into<Color>) {
+        self.set_symbol(x_axis, y_axis, symbol.encode_utf8(&mut [0; 4]), fg);
+    }
+
+    // Write a grapheme cluster, wide characters taking the cell after it too, and return the
+    // number of cells it takes. A wide character reaching past the edge of the buffer is
+    // replaced by a space, and wide characters partly overwritten are blanked, so no
+    // character is ever shown cut in two
+    pub fn set_symbol(
+        &mut self,
+        x_axis: u16,
+        y_axis: u16,
+        symbol: &str,
+        fg: impl Into<Color>,
+    ) -> u16 {
+        let fg = Some(fg.into());
+        let (symbol, width) = match symbols::grapheme_width(symbol) {
+            2 if self.index(x_axis.saturating_add(1), y_axis).is_none() => (" ", 1),
+            width => (symbol, width as u16),
+        };
+
+        let Some(cell) = self.get_mut(x_axis, y_axis) else {
+            return width;
+        };
+        cell.symbol = symbol.to_string();
+        cell.fg = fg;
+        cell.link = None;
+        cell.lines = LineJoin::default();
+        cell.transparent = false;
+        let (bg, style) = (cell.bg, cell.style);
+
+        if width == 2 {
+            if let Some(continuation) = self.get_mut(x_axis + 1, y_axis) {
+                *continuation = Cell {
+                    symbol: String::new(),
+                    fg,
+                    bg,
+                    style,
+                    ..Cell::default()
+                };
+            }
+        }
+        self.mend(
+            x_axis.saturating_sub(1)..x_axis.saturating_add(width + 1),
+            y_axis,
+        );
+        width
+    }
+
+    // Blank the wide characters of a row range that lost one of their two cells
+    pub(crate) fn mend(&mut self, columns: Range<u16>, y_axis: u16) {
+        for x_axis in columns {
+            let leading = x_axis
+                .checked_sub(1)
+                .and_then(|before| self.get(before, y_axis))
+                .is_some_and(|cell| cell.width() == 2);
+            let continued = x_axis
+                .checked_add(1)
+                .and_then(|after| self.get(after, y_axis))
+                .is_some_and(Cell::is_continuation);
+            let orphaned = self.get(x_axis, y_axis).is_some_and(|cell| {
+                (cell.is_continuation() && !leading) || (cell.width() == 2 && !continued)
+            });
+            if orphaned {
+                if let Some(cell) = self.get_mut(x_axis, y_axis) {
+                    cell.blank();
+                }
+            }
+        }
+    }
+
+    // Draw a border glyph, joining its lines with the lines already in the cell so
+    // crossing borders turn into junctions such as `┬` or `╬` instead of overwriting each other
+    pub fn set_line(&mut self, x_axis: u16, y_axis: u16, symbol: char, fg: impl Into<Color>) {
+        let Some(lines) = LineJoin::from_glyph(symbol) else {
+            self.set_char(x_axis, y_axis, symbol, fg);
+            return;
+        };
+        let Some(cell) = self.get_mut(x_axis, y_axis) else {
+            return;
+        };
+
+        let merged = cell.lines.merge(lines);
+        cell.fg = Some(fg.into());
+        cell.link = None;
+        cell.transparent = false;
+        // Keep the exact glyph (e.g. a rounded or dashed one) when the cell adds nothing to it
+        match merged.closest_glyph(lines) {
+            Some((glyph, merged)) if merged != lines => {
+                cell.symbol = glyph.to_string();
+                cell.lines = merged;
+            }
+            _ => {
+                cell.symbol = symbol.to_string();
+                cell.lines = lines;
+            }
+        }
+        self.mend(x_axis.saturating_sub(1)..x_axis.saturating_add(2), y_axis);
+    }
+
+    // Cells that were drawn into, blank ones included, with their absolute position
+    pub fn drawn(&self) -> impl Iterator<Item = (u16, u16, &Cell)> {
+        let area = self.area;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.drawn[*index])
+            .map(move |(index, cell)| {
+                let x_axis = area.x + (index % area.width as usize) as u16;
+                let y_axis = area.y + (index / area.width as usize) as u16;
+                (x_axis, y_axis, cell)
+            })
+    }
+
+    // Write every cell that was drawn into the buffer, leaving the others untouched
+    pub fn flush(&self, backend: &mut dyn Backend) -> Result<(), io::Error> {
+        backend.draw(self)?;
+        backend.flush()
+    }
+}
@@ -1,21 +1,548 @@
-#[derive(Clone)]
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::palette::Palette;
+
+// Define a 24-bit color, written as '#RRGGBB' in configuration files
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct HexColor {
-    code: String,
+    r: u8,
+    g: u8,
+    b: u8,
 }
 
 impl HexColor {
+    pub const BLACK: Self = Self::from_rgb(0, 0, 0);
+    pub const WHITE: Self = Self::from_rgb(255, 255, 255);
+
+    // Build a color from a code that is trusted to be valid, the channels that cannot be
+    // read are left at 0
     pub fn new(code: &str) -> Self {
-        Self {
-            code: String::from(code),
+        let channel = |range| {
+            code.get(range)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .unwrap_or(0)
+        };
+        Self::from_rgb(channel(1..3), channel(3..5), channel(5..7))
+    }
+
+    // Parse a color, rejecting anything that is not in the format '#RRGGBB'
+    pub fn parse(code: &str) -> Result<Self, String> {
+        let valid = code.len() == 7
+            && code.starts_with('#')
+            && code[1..].chars().all(|c| c.is_ascii_hexdigit());
+
+        if !valid {
+            return Err(format!(
+                "invalid color `{}`, expected the format #RRGGBB",
+                code
+            ));
         }
+        Ok(Self::new(code))
+    }
+
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    // Split the color into its red, green and blue components
+    pub fn components(&self) -> (u8, u8, u8) {
+        (self.r, self.g, self.b)
     }
 
     // Convert HexColor to crossterm's Color
+    #[cfg(feature = "crossterm")]
     pub fn to_rgb(&self) -> crossterm::style::Color {
-        // Assuming the HexColor is in the format '#RRGGBB'
-        let r = u8::from_str_radix(&self.code[1..3], 16).unwrap_or(0);
-        let g = u8::from_str_radix(&self.code[3..5], 16).unwrap_or(0);
-        let b = u8::from_str_radix(&self.code[5..7], 16).unwrap_or(0);
-        crossterm::style::Color::Rgb { r, g, b }
+        crossterm::style::Color::Rgb {
+            r: self.r,
+            g: self.g,
+            b: self.b,
+        }
+    }
+
+    // Hue in degrees, saturation and lightness between 0.0 and 1.0
+    pub fn to_hsl(&self) -> (f64, f64, f64) {
+        rgb_to_hsl(to_unit(self))
+    }
+
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        from_unit(hsl_to_rgb((
+            hue.rem_euclid(360.0),
+            saturation.clamp(0.0, 1.0),
+            lightness.clamp(0.0, 1.0),
+        )))
+    }
+
+    // Hue in degrees, saturation and value between 0.0 and 1.0
+    pub fn to_hsv(&self) -> (f64, f64, f64) {
+        let (r, g, b) = to_unit(self);
+        let max = r.max(g).max(b);
+        let delta = max - r.min(g).min(b);
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+        (hue_of((r, g, b), max, delta), saturation, max)
+    }
+
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Self {
+        let (saturation, value) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+        let chroma = value * saturation;
+        from_unit(hue_to_rgb(hue.rem_euclid(360.0), chroma, value - chroma))
+    }
+
+    // Perceptual lightness between 0.0 and 1.0, chroma (about 0.0 to 0.37 for sRGB colors)
+    // and hue in degrees
+    pub fn to_oklch(&self) -> (f64, f64, f64) {
+        let (lightness, a, b) = rgb_to_oklab(to_unit(self));
+        let hue = b.atan2(a).to_degrees().rem_euclid(360.0);
+        (lightness, a.hypot(b), hue)
+    }
+
+    // Colors outside of the sRGB gamut are clipped
+    pub fn from_oklch(lightness: f64, chroma: f64, hue: f64) -> Self {
+        let (sin, cos) = hue.to_radians().sin_cos();
+        from_unit(oklab_to_rgb((lightness, chroma * cos, chroma * sin)))
+    }
+
+    // Raise the HSL lightness by `amount`, 1.0 giving white
+    pub fn lighten(&self, amount: f64) -> Self {
+        let (hue, saturation, lightness) = self.to_hsl();
+        Self::from_hsl(hue, saturation, lightness + amount)
+    }
+
+    pub fn darken(&self, amount: f64) -> Self {
+        self.lighten(-amount)
+    }
+
+    // Raise the HSL saturation by `amount`, 1.0 giving the pure hue
+    pub fn saturate(&self, amount: f64) -> Self {
+        let (hue, saturation, lightness) = self.to_hsl();
+        Self::from_hsl(hue, saturation + amount, lightness)
+    }
+
+    pub fn desaturate(&self, amount: f64) -> Self {
+        self.saturate(-amount)
+    }
+
+    pub fn grayscale(&self) -> Self {
+        self.desaturate(1.0)
+    }
+
+    pub fn invert(&self) -> Self {
+        Self::from_rgb(255 - self.r, 255 - self.g, 255 - self.b)
+    }
+
+    pub fn rotate_hue(&self, degrees: f64) -> Self {
+        let (hue, saturation, lightness) = self.to_hsl();
+        Self::from_hsl(hue + degrees, saturation, lightness)
+    }
+
+    // Blend the channels with `other`, `amount` 0.0 giving this color and 1.0 `other`
+    pub fn mix(&self, other: &Self, amount: f64) -> Self {
+        let amount = amount.clamp(0.0, 1.0);
+        from_unit(lerp3(to_unit(self), to_unit(other), amount))
+    }
+
+    // Paint this color with the opacity `alpha` over `background`
+    pub fn blend(&self, background: &Self, alpha: f64) -> Self {
+        background.mix(self, alpha)
+    }
+
+    // Color on the opposite side of the hue wheel
+    pub fn complementary(&self) -> Self {
+        self.rotate_hue(180.0)
+    }
+
+    // The two neighbours `angle` degrees away on either side of the hue wheel
+    pub fn analogous(&self, angle: f64) -> [Self; 2] {
+        [self.rotate_hue(-angle), self.rotate_hue(angle)]
+    }
+
+    // The two colors splitting the hue wheel in three with this one
+    pub fn triadic(&self) -> [Self; 2] {
+        [self.rotate_hue(120.0), self.rotate_hue(240.0)]
+    }
+
+    // `count` shades from this color to white, e.g. for hover and selection backgrounds
+    pub fn tints(&self, count: usize) -> Vec<Self> {
+        (1..=count)
+            .map(|step| self.mix(&Self::WHITE, step as f64 / (count + 1) as f64))
+            .collect()
+    }
+
+    // `count` shades from this color to black
+    pub fn shades(&self, count: usize) -> Vec<Self> {
+        (1..=count)
+            .map(|step| self.mix(&Self::BLACK, step as f64 / (count + 1) as f64))
+            .collect()
+    }
+
+    // Relative luminance as defined by WCAG 2, 0.0 for black and 1.0 for white
+    pub fn luminance(&self) -> f64 {
+        let (r, g, b) = to_unit(self);
+        0.2126 * srgb_to_linear(r) + 0.7152 * srgb_to_linear(g) + 0.0722 * srgb_to_linear(b)
+    }
+
+    // WCAG contrast ratio, from 1.0 for the same colors to 21.0 for black on white.
+    // Text needs at least 4.5 for level AA and 7.0 for level AAA
+    pub fn contrast_ratio(&self, other: &Self) -> f64 {
+        let (first, second) = (self.luminance(), other.luminance());
+        (first.max(second) + 0.05) / (first.min(second) + 0.05)
+    }
+
+    pub fn is_dark(&self) -> bool {
+        self.contrast_ratio(&Self::WHITE) > self.contrast_ratio(&Self::BLACK)
+    }
+}
+
+impl fmt::Display for HexColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r, self.g, self.b)
+    }
+}
+
+impl TryFrom<String> for HexColor {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, Self::Error> {
+        Self::parse(&code)
+    }
+}
+
+impl From<HexColor> for String {
+    fn from(color: HexColor) -> Self {
+        color.to_string()
+    }
+}
+
+// Define a color as the terminal sees it: its default color, one of the 16 named colors or
+// 256 indexed colors of its palette, which follow the user's terminal theme, or an exact RGB
+// color. Written as "default", a name such as "bright_blue", an index or '#RRGGBB'
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+#[serde(try_from = "ColorValue", into = "ColorValue")]
+pub enum Color {
+    #[default]
+    Reset,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Indexed(u8),
+    Rgb(HexColor),
+}
+
+impl Color {
+    // The named colors, in the order of their palette index
+    pub const NAMED: [Self; 16] = [
+        Self::Black,
+        Self::Red,
+        Self::Green,
+        Self::Yellow,
+        Self::Blue,
+        Self::Magenta,
+        Self::Cyan,
+        Self::White,
+        Self::BrightBlack,
+        Self::BrightRed,
+        Self::BrightGreen,
+        Self::BrightYellow,
+        Self::BrightBlue,
+        Self::BrightMagenta,
+        Self::BrightCyan,
+        Self::BrightWhite,
+    ];
+
+    const NAMES: [&'static str; 16] = [
+        "black",
+        "red",
+        "green",
+        "yellow",
+        "blue",
+        "magenta",
+        "cyan",
+        "white",
+        "bright_black",
+        "bright_red",
+        "bright_green",
+        "bright_yellow",
+        "bright_blue",
+        "bright_magenta",
+        "bright_cyan",
+        "bright_white",
+    ];
+
+    // Palette index of the color, None for the default and RGB colors
+    pub fn index(&self) -> Option<u8> {
+        match self {
+            Self::Reset | Self::Rgb(_) => None,
+            Self::Indexed(index) => Some(*index),
+            named => Self::NAMED
+                .iter()
+                .position(|color| color == named)
+                .map(|index| index as u8),
+        }
     }
+
+    // The color shown by a terminal with the given palette, None for the default color as it
+    // depends on whether it is used for text or for the background
+    pub fn resolve(&self, palette: &Palette) -> Option<HexColor> {
+        match self {
+            Self::Reset => None,
+            Self::Rgb(color) => Some(*color),
+            other => other.index().map(|index| palette.get(index)),
+        }
+    }
+
+    // The color shown with the palette installed by `Palette::install`
+    pub fn to_hex(&self) -> Option<HexColor> {
+        self.resolve(&Palette::current())
+    }
+
+    // Apply an RGB transformation, e.g. dimming, resolving palette colors first
+    pub fn map_hex(&self, transform: impl FnOnce(HexColor) -> HexColor) -> Self {
+        match self.to_hex() {
+            Some(color) => Self::Rgb(transform(color)),
+            None => *self,
+        }
+    }
+
+    // Convert Color to crossterm's Color, keeping palette colors as palette colors
+    #[cfg(feature = "crossterm")]
+    pub fn to_crossterm(&self) -> crossterm::style::Color {
+        use crossterm::style::Color as Crossterm;
+
+        match self {
+            Self::Reset => Crossterm::Reset,
+            Self::Black => Crossterm::Black,
+            Self::Red => Crossterm::DarkRed,
+            Self::Green => Crossterm::DarkGreen,
+            Self::Yellow => Crossterm::DarkYellow,
+            Self::Blue => Crossterm::DarkBlue,
+            Self::Magenta => Crossterm::DarkMagenta,
+            Self::Cyan => Crossterm::DarkCyan,
+            Self::White => Crossterm::Grey,
+            Self::BrightBlack => Crossterm::DarkGrey,
+            Self::BrightRed => Crossterm::Red,
+            Self::BrightGreen => Crossterm::Green,
+            Self::BrightYellow => Crossterm::Yellow,
+            Self::BrightBlue => Crossterm::Blue,
+            Self::BrightMagenta => Crossterm::Magenta,
+            Self::BrightCyan => Crossterm::Cyan,
+            Self::BrightWhite => Crossterm::White,
+            Self::Indexed(index) => Crossterm::AnsiValue(*index),
+            Self::Rgb(color) => color.to_rgb(),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reset => write!(f, "default"),
+            Self::Indexed(index) => write!(f, "{}", index),
+            Self::Rgb(color) => write!(f, "{}", color),
+            named => {
+                let index = named.index().unwrap_or(0) as usize;
+                write!(f, "{}", Self::NAMES[index])
+            }
+        }
+    }
+}
+
+impl From<HexColor> for Color {
+    fn from(color: HexColor) -> Self {
+        Self::Rgb(color)
+    }
+}
+
+impl From<&HexColor> for Color {
+    fn from(color: &HexColor) -> Self {
+        Self::Rgb(*color)
+    }
+}
+
+impl From<&Color> for Color {
+    fn from(color: &Color) -> Self {
+        *color
+    }
+}
+
+// Parse the forms accepted in configuration files, with the index written as text
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.parse() {
+            Ok(index) => Ok(Self::Indexed(index)),
+            Err(_) => Self::try_from(ColorValue::Name(text.to_string())),
+        }
+    }
+}
+
+// Define how a color is written in configuration files, as an index or as a string
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ColorValue {
+    Index(u8),
+    Name(String),
+}
+
+impl TryFrom<ColorValue> for Color {
+    type Error = String;
+
+    fn try_from(value: ColorValue) -> Result<Self, Self::Error> {
+        let name = match value {
+            ColorValue::Index(index) => return Ok(Self::Indexed(index)),
+            ColorValue::Name(name) => name,
+        };
+
+        if name.starts_with('#') {
+            return HexColor::parse(&name).map(Self::Rgb);
+        }
+        if name == "default" || name == "reset" {
+            return Ok(Self::Reset);
+        }
+        Self::NAMES
+            .iter()
+            .position(|candidate| *candidate == name)
+            .map(|index| Self::NAMED[index])
+            .ok_or_else(|| {
+                format!(
+                    "invalid color `{}`, expected #RRGGBB, a palette index, `default` or a name such as `bright_blue`",
+                    name
+                )
+            })
+    }
+}
+
+impl From<Color> for ColorValue {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Indexed(index) => Self::Index(index),
+            other => Self::Name(other.to_string()),
+        }
+    }
+}
+
+pub(crate) type Triple = (f64, f64, f64);
+
+pub(crate) fn lerp3(from: Triple, to: Triple, amount: f64) -> Triple {
+    (
+        from.0 + (to.0 - from.0) * amount,
+        from.1 + (to.1 - from.1) * amount,
+        from.2 + (to.2 - from.2) * amount,
+    )
+}
+
+// Channels scaled between 0.0 and 1.0
+pub(crate) fn to_unit(color: &HexColor) -> Triple {
+    let (r, g, b) = color.components();
+    (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0)
+}
+
+pub(crate) fn from_unit((r, g, b): Triple) -> HexColor {
+    let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    HexColor::from_rgb(channel(r), channel(g), channel(b))
+}
+
+fn srgb_to_linear(value: f64) -> f64 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f64) -> f64 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+pub(crate) fn rgb_to_oklab((r, g, b): Triple) -> Triple {
+    let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    (
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    )
+}
+
+pub(crate) fn oklab_to_rgb((lightness, a, b): Triple) -> Triple {
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    (
+        linear_to_srgb(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+        linear_to_srgb(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+        linear_to_srgb(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+    )
+}
+
+pub(crate) fn rgb_to_hsl((r, g, b): Triple) -> Triple {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    (hue_of((r, g, b), max, delta), saturation, lightness)
+}
+
+pub(crate) fn hsl_to_rgb((hue, saturation, lightness): Triple) -> Triple {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    hue_to_rgb(hue, chroma, lightness - chroma / 2.0)
+}
+
+// Hue shared by HSL and HSV, from the largest channel and the spread of the channels
+fn hue_of((r, g, b): Triple, max: f64, delta: f64) -> f64 {
+    if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    }
+}
+
+// Channels of the color with the given hue and chroma, each raised by `m`
+fn hue_to_rgb(hue: f64, chroma: f64, m: f64) -> Triple {
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+
+    let (r, g, b) = match hue as u32 {
+        0..=59 => (chroma, x, 0.0),
+        60..=119 => (x, chroma, 0.0),
+        120..=179 => (0.0, chroma, x),
+        180..=239 => (0.0, x, chroma),
+        240..=299 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    (r + m, g + m, b + m)
 }
@@ -0,0 +1,236 @@
+use std::io;
+
+use crate::backend::Backend;
+use crate::buffer::Buffer;
+use crate::event::{Event, MouseEvent};
+use crate::layer::Layer;
+use crate::layout::Rect;
+
+// Define the kinds of layers, each stacked above all the kinds listed before it
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LayerKind {
+    Base,      // The layer filling the whole terminal
+    Container, // Panels laid out on top of the base layer
+    Window,    // Floating windows
+    Popup,     // Menus and drop-downs opened from another layer
+    Modal,     // Dialogs capturing every input event until closed
+    Tooltip,   // Hints shown above everything else
+}
+
+// Handle returned when a layer is added, used to address it afterwards
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LayerId(usize);
+
+// Define a layer held by the compositor along with how it is stacked
+struct Entry {
+    id: LayerId,
+    kind: LayerKind,
+    z_index: i32,       // Order among the layers of the same kind, higher is on top
+    visible: bool,      // Hidden layers are neither drawn nor given events
+    clip: Option<Rect>, // Drawing is restricted to this area when set
+    layer: Box<dyn Layer>,
+}
+
+// Stack layers on top of each other and merge them into a single buffer. Layers show the
+// layers below through the cells they draw as `Cell::transparent()`
+pub struct Compositor {
+    area: Rect,
+    entries: Vec<Entry>, // Kept sorted from the bottom to the top of the stack
+    next_id: usize,
+    previous: Option<Buffer>, // Frame last rendered, None when the screen must be redrawn
+}
+
+impl Compositor {
+    pub fn new(area: Rect) -> Self {
+        Self {
+            area,
+            entries: Vec::new(),
+            next_id: 0,
+            previous: None,
+        }
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    pub fn resize(&mut self, area: Rect) {
+        self.area = area;
+        self.invalidate();
+    }
+
+    // Write the whole frame on the next render, e.g. after the screen was cleared
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    // Add a layer on top of the other layers of the same kind
+    pub fn push(&mut self, kind: LayerKind, layer: Box<dyn Layer>) -> LayerId {
+        let id = LayerId(self.next_id);
+        self.next_id += 1;
+
+        let z_index = self
+            .entries
+            .iter()
+            .filter(|entry| entry.kind == kind)
+            .map(|entry| entry.z_index + 1)
+            .max()
+            .unwrap_or(0);
+        self.entries.push(Entry {
+            id,
+            kind,
+            z_index,
+            visible: true,
+            clip: None,
+            layer,
+        });
+        self.sort();
+        id
+    }
+
+    pub fn remove(&mut self, id: LayerId) -> Option<Box<dyn Layer>> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        Some(self.entries.remove(index).layer)
+    }
+
+    pub fn get(&self, id: LayerId) -> Option<&dyn Layer> {
+        self.entry(id).map(|entry| entry.layer.as_ref())
+    }
+
+    pub fn get_mut(&mut self, id: LayerId) -> Option<&mut Box<dyn Layer>> {
+        self.entry_mut(id).map(|entry| &mut entry.layer)
+    }
+
+    // Identifiers of the layers from the bottom to the top of the stack
+    pub fn layers(&self) -> impl Iterator<Item = LayerId> + '_ {
+        self.entries.iter().map(|entry| entry.id)
+    }
+
+    pub fn set_z_index(&mut self, id: LayerId, z_index: i32) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.z_index = z_index;
+        }
+        self.sort();
+    }
+
+    // Move a layer above the other layers of the same kind, e.g. when a window gets focus
+    pub fn raise(&mut self, id: LayerId) {
+        let Some(kind) = self.entry(id).map(|entry| entry.kind) else {
+            return;
+        };
+        let top = self
+            .entries
+            .iter()
+            .filter(|entry| entry.kind == kind && entry.id != id)
+            .map(|entry| entry.z_index + 1)
+            .max()
+            .unwrap_or(0);
+        self.set_z_index(id, top);
+    }
+
+    pub fn set_visible(&mut self, id: LayerId, visible: bool) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.visible = visible;
+        }
+    }
+
+    // Restrict the drawing of a layer to an area, or lift the restriction with None
+    pub fn set_clip(&mut self, id: LayerId, clip: Option<Rect>) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.clip = clip;
+        }
+    }
+
+    // Merge the visible layers into one buffer, from the bottom of the stack to the top
+    pub fn compose(&self) -> Result<Buffer, io::Error> {
+        let mut buffer = Buffer::new(self.area);
+
+        for entry in self.entries.iter().filter(|entry| entry.visible) {
+            let mut area = entry.layer.area().intersection(self.area);
+            if let Some(clip) = entry.clip {
+                area = area.intersection(clip);
+            }
+            if area.width == 0 || area.height == 0 {
+                continue;
+            }
+
+            let mut layer_buffer = Buffer::new(area);
+            entry.layer.draw(&mut layer_buffer)?;
+            blend(&mut buffer, &layer_buffer);
+        }
+        Ok(buffer)
+    }
+
+    // Write the cells that changed since the previous render, including the ones uncovered
+    // by layers that were hidden, removed, moved or shrunk
+    pub fn render(&mut self, backend: &mut dyn Backend) -> Result<(), io::Error> {
+        let mut frame = self.compose()?;
+        match &self.previous {
+            Some(previous) => frame.diff(previous).flush(backend)?,
+            None => {
+                frame.touch();
+                frame.flush(backend)?;
+            }
+        }
+        self.previous = Some(frame);
+        Ok(())
+    }
+
+    // Offer an event to the layers from the top of the stack down, stopping at the first
+    // layer consuming it; a visible modal swallows every event so nothing below reacts.
+    // Mouse events only reach the layers under the pointer. Returns true when consumed.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        let pointer = match event {
+            Event::Mouse(MouseEvent { column, row, .. }) => Some((*column, *row)),
+            _ => None,
+        };
+
+        for entry in self.entries.iter_mut().rev().filter(|entry| entry.visible) {
+            let under_pointer =
+                pointer.is_none_or(|(column, row)| entry.layer.area().contains(column, row));
+            if under_pointer && entry.layer.handle_event(event) {
+                return true;
+            }
+            if entry.kind == LayerKind::Modal {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn entry(&self, id: LayerId) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    fn entry_mut(&mut self, id: LayerId) -> Option<&mut Entry> {
+        self.entries.iter_mut().find(|entry| entry.id == id)
+    }
+
+    // The sort is stable, so layers with the same kind and z-index keep their insertion order
+    fn sort(&mut self) {
+        self.entries
+            .sort_by_key(|entry| (entry.kind, entry.z_index));
+    }
+}
+
+// Copy the cells of a layer over the cells below it, except the transparent ones
+fn blend(buffer: &mut Buffer, layer: &Buffer) {
+    let area = layer.area();
+
+    for y_axis in area.y..area.y + area.height {
+        for x_axis in area.x..area.x + area.width {
+            let Some(cell) = layer.get(x_axis, y_axis) else {
+                continue;
+            };
+            if cell.is_transparent() {
+                continue;
+            }
+            if let Some(below) = buffer.get_mut(x_axis, y_axis) {
+                *below = cell.clone();
+            }
+        }
+        // Wide characters of the layers below partly covered, at the edges of the layer or
+        // next to its transparent cells, are blanked instead of shown cut in two
+        buffer.mend(area.x.saturating_sub(1)..area.x + area.width + 1, y_axis);
+    }
+}
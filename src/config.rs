@@ -0,0 +1,184 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::border::Border;
+use crate::contrast::ContrastCheck;
+use crate::font::{FontStyle, TextAlignment};
+use crate::layout::LayoutNode;
+use crate::theme::{Appearance, Theme};
+
+// Define the file formats a configuration can be loaded from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Ron,
+}
+
+impl ConfigFormat {
+    // Pick the format from the file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            "ron" => Some(Self::Ron),
+            _ => None,
+        }
+    }
+}
+
+// Define the errors that can occur while loading a configuration
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),                            // The file could not be read
+    UnsupportedFormat(PathBuf),               // The extension is not toml, json or ron
+    Parse { key: String, message: String },   // The file is malformed or a value has the wrong type
+    Invalid { key: String, message: String }, // The value parsed but is not acceptable
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "unable to read configuration: {}", error),
+            Self::UnsupportedFormat(path) => {
+                write!(f, "unsupported configuration format: {}", path.display())
+            }
+            Self::Parse { key, message } if key.is_empty() => write!(f, "{}", message),
+            Self::Parse { key, message } | Self::Invalid { key, message } => {
+                write!(f, "`{}`: {}", key, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+// Define everything tixel reads from a configuration file
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: Theme,
+    pub light_theme: Option<Theme>, // Replaces `theme` on terminals with a light background
+    pub dark_theme: Option<Theme>,  // Replaces `theme` on terminals with a dark background
+    pub appearance: Option<Appearance>, // Skip the detection of the terminal background
+    pub border: Option<Border>,     // Keeps the current border when absent
+    pub font_style: FontStyle,
+    pub text_alignment: TextAlignment,
+    pub layout: Option<LayoutNode>,
+    pub contrast: Option<ContrastCheck>, // Accessibility check of the text colors, off when absent
+}
+
+impl Config {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path)
+            .ok_or_else(|| ConfigError::UnsupportedFormat(path.to_path_buf()))?;
+        let source = fs::read_to_string(path)?;
+        Self::parse(&source, format)
+    }
+
+    // Theme to use on a terminal with the given appearance
+    pub fn theme_for(&self, appearance: Appearance) -> &Theme {
+        let variant = match appearance {
+            Appearance::Light => &self.light_theme,
+            Appearance::Dark => &self.dark_theme,
+        };
+        variant.as_ref().unwrap_or(&self.theme)
+    }
+
+    pub fn parse(source: &str, format: ConfigFormat) -> Result<Self, ConfigError> {
+        let config: Config = match format {
+            ConfigFormat::Toml => {
+                let deserializer = toml::Deserializer::new(source);
+                serde_path_to_error::deserialize(deserializer).map_err(parse_error)?
+            }
+            ConfigFormat::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(source);
+                serde_path_to_error::deserialize(&mut deserializer).map_err(parse_error)?
+            }
+            ConfigFormat::Ron => {
+                let mut deserializer =
+                    ron::Deserializer::from_str(source).map_err(|error| ConfigError::Parse {
+                        key: String::new(),
+                        message: error.to_string(),
+                    })?;
+                serde_path_to_error::deserialize(&mut deserializer).map_err(parse_error)?
+            }
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(border) = &self.border {
+            border.validate().map_err(|error| ConfigError::Invalid {
+                key: format!("border.{}", error.key()),
+                message: error.to_string(),
+            })?;
+        }
+
+        if let Some(layout) = &self.layout {
+            layout
+                .validate("layout")
+                .map_err(|(key, message)| ConfigError::Invalid { key, message })?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_error<E: fmt::Display>(error: serde_path_to_error::Error<E>) -> ConfigError {
+    let key = error.path().to_string();
+    ConfigError::Parse {
+        key: if key == "." { String::new() } else { key },
+        message: error.into_inner().to_string(),
+    }
+}
+
+// Watch a configuration file and reload it whenever it changes on disk
+pub struct ConfigWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let last_modified = Self::modified(&path);
+        Self {
+            path,
+            last_modified,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Meant to be called on every tick of the event loop; returns the reloaded
+    // configuration only when the file was modified since the previous call
+    pub fn poll(&mut self) -> Option<Result<Config, ConfigError>> {
+        let modified = Self::modified(&self.path);
+        if modified.is_none() || modified == self.last_modified {
+            return None;
+        }
+
+        self.last_modified = modified;
+        Some(Config::load(&self.path))
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|meta| meta.modified()).ok()
+    }
+}
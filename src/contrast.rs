@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+use crate::buffer::Buffer;
+use crate::palette::Palette;
+use crate::{Color, HexColor};
+
+// Colors of the cells marked by `ContrastAction::Highlight`
+const HIGHLIGHT_FG: Color = Color::Rgb(HexColor::from_rgb(0, 0, 0));
+const HIGHLIGHT_BG: Color = Color::Rgb(HexColor::from_rgb(255, 0, 255));
+
+// Define the WCAG conformance levels text can be held to
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContrastLevel {
+    #[default]
+    Aa, // Contrast ratio of at least 4.5
+    Aaa, // Contrast ratio of at least 7.0
+}
+
+impl ContrastLevel {
+    pub fn ratio(&self) -> f64 {
+        match self {
+            Self::Aa => 4.5,
+            Self::Aaa => 7.0,
+        }
+    }
+}
+
+// Define what happens to a cell whose text is too faint against its background
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContrastAction {
+    #[default]
+    Adjust, // Lighten or darken the text just enough to reach the level
+    Highlight, // Paint the cell in magenta, to spot the faulty colors while designing a theme
+}
+
+// Define the contrast check run over a frame before it is shown
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContrastCheck {
+    pub level: ContrastLevel,
+    pub action: ContrastAction,
+}
+
+impl ContrastCheck {
+    pub fn new(level: ContrastLevel, action: ContrastAction) -> Self {
+        Self { level, action }
+    }
+
+    // Check every drawn cell against its background, returning how many failed.
+    // Cells without colors of their own are shown in the terminal's default colors;
+    // palette colors are checked against the current palette
+    pub fn apply(&self, buffer: &mut Buffer) -> usize {
+        let palette = Palette::current();
+        let resolve = |color: Option<Color>, default: HexColor| {
+            color
+                .and_then(|color| color.resolve(&palette))
+                .unwrap_or(default)
+        };
+        let area = buffer.area();
+        let mut failed = 0;
+
+        for y_axis in area.y..area.y + area.height {
+            for x_axis in area.x..area.x + area.width {
+                // Cells are only drawn into when they fail, so the others are not flushed
+                let Some(cell) = buffer.get(x_axis, y_axis) else {
+                    continue;
+                };
+                if cell.symbol.trim().is_empty() {
+                    continue;
+                }
+
+                let fg = resolve(cell.fg, palette.foreground);
+                let bg = resolve(cell.bg, palette.background);
+                if fg.contrast_ratio(&bg) >= self.level.ratio() {
+                    continue;
+                }
+
+                failed += 1;
+                let (fg, bg) = match self.action {
+                    ContrastAction::Adjust => {
+                        (Some(readable(&fg, &bg, self.level.ratio()).into()), cell.bg)
+                    }
+                    ContrastAction::Highlight => (Some(HIGHLIGHT_FG), Some(HIGHLIGHT_BG)),
+                };
+                if let Some(cell) = buffer.get_mut(x_axis, y_axis) {
+                    cell.fg = fg;
+                    cell.bg = bg;
+                }
+            }
+        }
+        failed
+    }
+}
+
+// The color closest to `fg` with at least the contrast `ratio` against `bg`, moving it
+// toward white over dark backgrounds and toward black over light ones. When even white or
+// black falls short, the one with the most contrast is returned
+pub fn readable(fg: &HexColor, bg: &HexColor, ratio: f64) -> HexColor {
+    if fg.contrast_ratio(bg) >= ratio {
+        return *fg;
+    }
+
+    let (first, second) = if bg.is_dark() {
+        (HexColor::WHITE, HexColor::BLACK)
+    } else {
+        (HexColor::BLACK, HexColor::WHITE)
+    };
+
+    for target in [first, second] {
+        if target.contrast_ratio(bg) < ratio {
+            continue;
+        }
+        // The contrast grows steadily while mixing toward the target, so search the
+        // smallest amount that is enough
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..16 {
+            let amount = (low + high) / 2.0;
+            if fg.mix(&target, amount).contrast_ratio(bg) >= ratio {
+                high = amount;
+            } else {
+                low = amount;
+            }
+        }
+        return fg.mix(&target, high);
+    }
+
+    if first.contrast_ratio(bg) >= second.contrast_ratio(bg) {
+        first
+    } else {
+        second
+    }
+}
@@ -0,0 +1,107 @@
+// Input events, independent of the backend reading them from the terminal
+
+// Define an input event
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+    Key(KeyEvent),     // A key was pressed
+    Mouse(MouseEvent), // The mouse was clicked, dragged, moved or scrolled
+    Resize(u16, u16),  // The terminal now has the given number of columns and rows
+    Paste(String),     // Text pasted while bracketed paste is enabled
+    FocusGained,
+    FocusLost,
+}
+
+// Define the keys tixel tells apart
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+    BackTab,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    F(u8), // Function keys, F(1) being F1
+}
+
+// Define the modifier keys held during an event
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+}
+
+impl KeyModifiers {
+    pub const NONE: KeyModifiers = KeyModifiers::new(false, false, false);
+    pub const SHIFT: KeyModifiers = KeyModifiers::new(true, false, false);
+    pub const CONTROL: KeyModifiers = KeyModifiers::new(false, true, false);
+    pub const ALT: KeyModifiers = KeyModifiers::new(false, false, true);
+
+    pub const fn new(shift: bool, control: bool, alt: bool) -> Self {
+        Self {
+            shift,
+            control,
+            alt,
+        }
+    }
+
+    // Modifiers held in either set
+    pub const fn union(self, other: KeyModifiers) -> Self {
+        Self::new(
+            self.shift || other.shift,
+            self.control || other.control,
+            self.alt || other.alt,
+        )
+    }
+}
+
+// Define a key press; backends report presses only, not releases or repeats
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyEvent {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+}
+
+// Define the mouse buttons
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+// Define what the mouse did
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MouseEventKind {
+    Down(MouseButton),
+    Up(MouseButton),
+    Drag(MouseButton), // Moved while the button is held
+    Moved,             // Moved without any button held
+    ScrollUp,
+    ScrollDown,
+}
+
+// Define a mouse event at a zero-based cell position
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub column: u16,
+    pub row: u16,
+    pub modifiers: KeyModifiers,
+}
@@ -0,0 +1,374 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::backend::ansi::{LinkState, Pen};
+use crate::buffer::{Buffer, Cell};
+use crate::font::{FontStyle, Modifier, Underline};
+use crate::palette::Palette;
+use crate::theme::Theme;
+use crate::{Color, HexColor};
+
+// Size of a cell in SVG exports, matching a 14px monospace font
+const FONT_SIZE: f64 = 14.0;
+const CELL_WIDTH: f64 = 8.4;
+const CELL_HEIGHT: f64 = 17.0;
+
+// Define the formats a frame can be exported to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    Html,                 // Standalone page with inline styles
+    Svg,                  // Vector image with a monospace font
+    Ansi { links: bool }, // Escape sequences that can be printed with `cat`, links as OSC 8
+    Text,                 // The symbols alone, without colors or styles
+}
+
+impl ExportFormat {
+    // Pick the format from the file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "html" | "htm" => Some(Self::Html),
+            "svg" => Some(Self::Svg),
+            "ans" | "ansi" => Some(Self::Ansi { links: false }),
+            "txt" => Some(Self::Text),
+            _ => None,
+        }
+    }
+}
+
+// Export a frame; cells without a color of their own take the colors of the theme and
+// palette colors take the colors of the current palette
+pub fn export(buffer: &Buffer, theme: &Theme, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Html => to_html(buffer, theme),
+        ExportFormat::Svg => to_svg(buffer, theme),
+        ExportFormat::Ansi { links } => to_ansi(buffer, links),
+        ExportFormat::Text => to_text(buffer),
+    }
+}
+
+// Export a frame to a file in the format matching its extension
+pub fn save(buffer: &Buffer, theme: &Theme, path: impl AsRef<Path>) -> Result<(), io::Error> {
+    let path = path.as_ref();
+    let format = ExportFormat::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported export format: {}", path.display()),
+        )
+    })?;
+    fs::write(path, export(buffer, theme, format))
+}
+
+// The frame with SGR escape sequences for colors and styles, one line per row.
+// Cells without a color keep the terminal's own colors. Links are written as OSC 8
+// sequences when `links` is set, for terminals that open them
+pub fn to_ansi(buffer: &Buffer, links: bool) -> String {
+    let mut ansi = String::new();
+
+    for row in rows(buffer) {
+        let mut pen = Pen::default();
+        let mut state = LinkState::new(links);
+        for run in runs(&row) {
+            let next = Pen {
+                fg: *run.fg,
+                bg: *run.bg,
+                style: run.style,
+            };
+            ansi.push_str(&state.switch(run.link.as_deref()));
+            ansi.push_str(&pen.transition(&next));
+            ansi.push_str(&run.text);
+            pen = next;
+        }
+        ansi.push_str(&state.switch(None));
+        if pen != Pen::default() {
+            ansi.push_str("\x1b[0m");
+        }
+        ansi.push('\n');
+    }
+    ansi
+}
+
+// The symbols of the frame, one line per row; wide characters are written once
+pub fn to_text(buffer: &Buffer) -> String {
+    let area = buffer.area();
+    let mut text = String::new();
+    for y_axis in area.y..area.y + area.height {
+        for x_axis in area.x..area.x + area.width {
+            text.push_str(
+                buffer
+                    .get(x_axis, y_axis)
+                    .map_or(" ", |cell| cell.symbol.as_str()),
+            );
+        }
+        text.push('\n');
+    }
+    text
+}
+
+pub fn to_html(buffer: &Buffer, theme: &Theme) -> String {
+    let palette = Palette::current();
+    let (foreground, background) = theme_colors(theme, &palette);
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>tixel</title>\n</head>\n\
+         <body style=\"margin:0\">\n<pre style=\"margin:0;font-family:monospace;line-height:1.2;\
+         color:{};background-color:{}\">",
+        foreground, background
+    );
+
+    for row in rows(buffer) {
+        for run in runs(&row) {
+            let (fg, bg) = run.colors(&palette, (foreground, background));
+            let mut style = String::new();
+            if let Some(fg) = fg {
+                let _ = write!(style, "color:{};", fg);
+            }
+            if let Some(bg) = bg {
+                let _ = write!(style, "background-color:{};", bg);
+            }
+            style.push_str(&css_style(run.style, &palette));
+
+            let mut text = escape(&run.text);
+            if !style.is_empty() {
+                text = format!("<span style=\"{}\">{}</span>", style, text);
+            }
+            match run.link {
+                Some(url) => {
+                    let _ = write!(
+                        html,
+                        "<a href=\"{}\" style=\"color:inherit\">{}</a>",
+                        escape(url),
+                        text
+                    );
+                }
+                None => html.push_str(&text),
+            }
+        }
+        html.push('\n');
+    }
+
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+pub fn to_svg(buffer: &Buffer, theme: &Theme) -> String {
+    let area = buffer.area();
+    let width = area.width as f64 * CELL_WIDTH;
+    let height = area.height as f64 * CELL_HEIGHT;
+    let palette = Palette::current();
+    let (foreground, background) = theme_colors(theme, &palette);
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.1}\" height=\"{:.1}\" viewBox=\"0 0 {:.1} {:.1}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n\
+         <g font-family=\"monospace\" font-size=\"{}\" xml:space=\"preserve\">\n",
+        width,
+        height,
+        width,
+        height,
+        background,
+        FONT_SIZE
+    );
+
+    for (index, row) in rows(buffer).enumerate() {
+        let y_axis = index as f64 * CELL_HEIGHT;
+        let mut column = 0;
+
+        for run in runs(&row) {
+            let x_axis = column as f64 * CELL_WIDTH;
+            let length = run.width;
+            column += length;
+
+            let (fg, bg) = run.colors(&palette, (foreground, background));
+            if let Some(bg) = bg {
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"/>",
+                    x_axis,
+                    y_axis,
+                    length as f64 * CELL_WIDTH,
+                    CELL_HEIGHT,
+                    bg
+                );
+            }
+            let decorated = run.style.underline()
+                || run.style.contains(Modifier::STRIKETHROUGH)
+                || run.style.contains(Modifier::OVERLINE);
+            if (run.text.trim().is_empty() && !decorated) || run.style.contains(Modifier::HIDDEN) {
+                continue;
+            }
+
+            let fg = fg.unwrap_or(foreground);
+            let css = css_style(run.style, &palette);
+            let attributes = if css.is_empty() {
+                String::new()
+            } else {
+                format!(" style=\"{}\"", css)
+            };
+            // Stretch the text over its cells so glyph widths of the font cannot shift the columns
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" fill=\"{}\" textLength=\"{:.1}\" \
+                 lengthAdjust=\"spacingAndGlyphs\"{}>{}</text>",
+                x_axis,
+                y_axis + CELL_HEIGHT * 0.8,
+                fg,
+                length as f64 * CELL_WIDTH,
+                attributes,
+                escape(&run.text)
+            );
+        }
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+// Define consecutive cells of a row sharing the same colors and style
+struct Run<'a> {
+    fg: &'a Option<Color>,
+    bg: &'a Option<Color>,
+    style: FontStyle,
+    link: &'a Option<String>,
+    text: String,
+    width: usize, // Number of cells covered by the text
+}
+
+impl Run<'_> {
+    // Colors the run is painted with, None standing for the colors of the theme. Reversed
+    // runs swap them, and hidden runs are drawn in their background color
+    fn colors(
+        &self,
+        palette: &Palette,
+        (foreground, background): (HexColor, HexColor),
+    ) -> (Option<HexColor>, Option<HexColor>) {
+        let fg = self.fg.and_then(|fg| fg.resolve(palette));
+        let bg = self.bg.and_then(|bg| bg.resolve(palette));
+        let (fg, bg) = if self.style.contains(Modifier::REVERSE) {
+            (
+                Some(bg.unwrap_or(background)),
+                Some(fg.unwrap_or(foreground)),
+            )
+        } else {
+            (fg, bg)
+        };
+
+        if self.style.contains(Modifier::HIDDEN) {
+            (Some(bg.unwrap_or(background)), bg)
+        } else {
+            (fg, bg)
+        }
+    }
+}
+
+// Colors of the theme, the default colors of the terminal standing for `Color::Reset`
+fn theme_colors(theme: &Theme, palette: &Palette) -> (HexColor, HexColor) {
+    (
+        theme
+            .foreground_color
+            .resolve(palette)
+            .unwrap_or(palette.foreground),
+        theme
+            .background_color
+            .resolve(palette)
+            .unwrap_or(palette.background),
+    )
+}
+
+fn rows(buffer: &Buffer) -> impl Iterator<Item = Vec<&Cell>> {
+    let area = buffer.area();
+    (area.y..area.y + area.height).map(move |y_axis| {
+        (area.x..area.x + area.width)
+            .filter_map(|x_axis| buffer.get(x_axis, y_axis))
+            .collect()
+    })
+}
+
+// The second cell of a wide character is covered by the first one, so it adds no text
+fn runs<'a>(row: &[&'a Cell]) -> Vec<Run<'a>> {
+    let mut runs: Vec<Run<'a>> = Vec::new();
+    for cell in row.iter().filter(|cell| !cell.is_continuation()) {
+        match runs.last_mut() {
+            Some(run)
+                if *run.fg == cell.fg
+                    && *run.bg == cell.bg
+                    && run.style == cell.style
+                    && *run.link == cell.link =>
+            {
+                run.text.push_str(&cell.symbol);
+                run.width += cell.width();
+            }
+            _ => runs.push(Run {
+                fg: &cell.fg,
+                bg: &cell.bg,
+                style: cell.style,
+                link: &cell.link,
+                text: cell.symbol.clone(),
+                width: cell.width(),
+            }),
+        }
+    }
+    runs
+}
+
+// Browsers have no blinking text, so blink is left out
+fn css_style(style: FontStyle, palette: &Palette) -> String {
+    let mut css = String::new();
+    if style.bold() {
+        css.push_str("font-weight:bold;");
+    }
+    if style.contains(Modifier::DIM) {
+        css.push_str("opacity:0.5;");
+    }
+    if style.italic() {
+        css.push_str("font-style:italic;");
+    }
+
+    let lines: Vec<&str> = [
+        (style.underline(), "underline"),
+        (style.contains(Modifier::STRIKETHROUGH), "line-through"),
+        (style.contains(Modifier::OVERLINE), "overline"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, line)| enabled.then_some(line))
+    .collect();
+    if !lines.is_empty() {
+        let _ = write!(css, "text-decoration-line:{};", lines.join(" "));
+    }
+    let shape = match style.underline_style() {
+        Underline::Double => Some("double"),
+        Underline::Curly => Some("wavy"),
+        Underline::Dotted => Some("dotted"),
+        Underline::Dashed => Some("dashed"),
+        Underline::None | Underline::Single => None,
+    };
+    if let Some(shape) = shape {
+        let _ = write!(css, "text-decoration-style:{};", shape);
+    }
+    if let Some(color) = style
+        .underline_color()
+        .and_then(|color| color.resolve(palette))
+    {
+        let _ = write!(css, "text-decoration-color:{};", color);
+    }
+    css
+}
+
+// Escape the characters with a meaning in HTML and XML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for symbol in text.chars() {
+        match symbol {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(symbol),
+        }
+    }
+    escaped
+}
@@ -1,21 +1,316 @@
+use std::ops::{BitOr, BitOrAssign, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Color;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TextAlignment {
+    #[default]
     Left,
     Center,
     Right,
 }
 
+// Define the direction in which the lines of a text are read. Left and right alignments are
+// mirrored for right-to-left lines
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum TextDirection {
+    #[default]
+    #[serde(rename = "auto")]
+    Auto, // Taken from the first letter of each line with a direction
+    #[serde(rename = "ltr")]
+    LeftToRight,
+    #[serde(rename = "rtl")]
+    RightToLeft,
+}
+
+// Define a set of text attributes, combined with `|`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Modifier(u16);
+
+impl Modifier {
+    pub const NONE: Modifier = Modifier(0);
+    pub const BOLD: Modifier = Modifier(1);
+    pub const DIM: Modifier = Modifier(1 << 1);
+    pub const ITALIC: Modifier = Modifier(1 << 2);
+    pub const BLINK: Modifier = Modifier(1 << 3);
+    pub const REVERSE: Modifier = Modifier(1 << 4); // Swap the text and background colors
+    pub const HIDDEN: Modifier = Modifier(1 << 5);
+    pub const STRIKETHROUGH: Modifier = Modifier(1 << 6);
+    pub const OVERLINE: Modifier = Modifier(1 << 7);
+
+    pub const fn contains(self, other: Modifier) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    // Whether the sets have an attribute in common
+    pub const fn intersects(self, other: Modifier) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    // Attributes in either set
+    pub const fn union(self, other: Modifier) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    // Attributes of this set missing from the other one
+    pub const fn difference(self, other: Modifier) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl BitOr for Modifier {
+    type Output = Modifier;
+
+    fn bitor(self, other: Modifier) -> Modifier {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for Modifier {
+    fn bitor_assign(&mut self, other: Modifier) {
+        *self = self.union(other);
+    }
+}
+
+impl Sub for Modifier {
+    type Output = Modifier;
+
+    fn sub(self, other: Modifier) -> Modifier {
+        self.difference(other)
+    }
+}
+
+// Define the shape of the line drawn under text
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Underline {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+// Define how text is drawn: its attributes, its underline and the color of the underline.
+// Written in configuration files as e.g. `{ bold = true, underline = "curly" }`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "FontStyleConfig", into = "FontStyleConfig")]
 pub struct FontStyle {
-    bold: bool,
-    italic: bool,
-    underline: bool,
+    modifiers: Modifier,
+    underline: Underline,
+    underline_color: Option<Color>, // The color of the text when None
 }
 
 impl FontStyle {
     pub fn new(bold: bool, italic: bool, underline: bool) -> Self {
+        let mut modifiers = Modifier::NONE;
+        if bold {
+            modifiers |= Modifier::BOLD;
+        }
+        if italic {
+            modifiers |= Modifier::ITALIC;
+        }
+        Self {
+            modifiers,
+            underline: if underline {
+                Underline::Single
+            } else {
+                Underline::None
+            },
+            underline_color: None,
+        }
+    }
+
+    pub fn modifiers(&self) -> Modifier {
+        self.modifiers
+    }
+
+    pub fn contains(&self, modifiers: Modifier) -> bool {
+        self.modifiers.contains(modifiers)
+    }
+
+    pub fn bold(&self) -> bool {
+        self.modifiers.contains(Modifier::BOLD)
+    }
+
+    pub fn italic(&self) -> bool {
+        self.modifiers.contains(Modifier::ITALIC)
+    }
+
+    pub fn underline(&self) -> bool {
+        self.underline != Underline::None
+    }
+
+    pub fn underline_style(&self) -> Underline {
+        self.underline
+    }
+
+    pub fn underline_color(&self) -> Option<Color> {
+        self.underline_color
+    }
+
+    pub fn with_underline(mut self, underline: Underline) -> Self {
+        self.underline = underline;
+        self
+    }
+
+    pub fn with_underline_color(mut self, color: impl Into<Option<Color>>) -> Self {
+        self.underline_color = color.into();
+        self
+    }
+
+    // Add the attributes of another style; its underline and underline color replace
+    // these when it has them
+    pub fn patch(mut self, other: impl Into<FontStyle>) -> Self {
+        let other = other.into();
+        self.modifiers |= other.modifiers;
+        if other.underline != Underline::None {
+            self.underline = other.underline;
+        }
+        if other.underline_color.is_some() {
+            self.underline_color = other.underline_color;
+        }
+        self
+    }
+
+    // Take away the attributes of another style, along with the underline and the
+    // underline color when it has them
+    pub fn remove(mut self, other: impl Into<FontStyle>) -> Self {
+        let other = other.into();
+        self.modifiers = self.modifiers - other.modifiers;
+        if other.underline != Underline::None {
+            self.underline = Underline::None;
+        }
+        if other.underline_color.is_some() {
+            self.underline_color = None;
+        }
+        self
+    }
+}
+
+impl From<Modifier> for FontStyle {
+    fn from(modifiers: Modifier) -> Self {
         Self {
-            bold,
-            italic,
+            modifiers,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<Underline> for FontStyle {
+    fn from(underline: Underline) -> Self {
+        Self::default().with_underline(underline)
+    }
+}
+
+impl<T: Into<FontStyle>> BitOr<T> for FontStyle {
+    type Output = FontStyle;
+
+    fn bitor(self, other: T) -> FontStyle {
+        self.patch(other)
+    }
+}
+
+impl<T: Into<FontStyle>> BitOrAssign<T> for FontStyle {
+    fn bitor_assign(&mut self, other: T) {
+        *self = self.patch(other);
+    }
+}
+
+// Define how a font style is written in configuration files
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FontStyleConfig {
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: UnderlineConfig,
+    underline_color: Option<Color>,
+    blink: bool,
+    reverse: bool,
+    hidden: bool,
+    strikethrough: bool,
+    overline: bool,
+}
+
+// Define an underline written either as a switch or as the name of its shape
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum UnderlineConfig {
+    Enabled(bool),
+    Style(Underline),
+}
+
+impl Default for UnderlineConfig {
+    fn default() -> Self {
+        Self::Enabled(false)
+    }
+}
+
+impl FontStyleConfig {
+    fn flags(&self) -> [(bool, Modifier); 8] {
+        [
+            (self.bold, Modifier::BOLD),
+            (self.dim, Modifier::DIM),
+            (self.italic, Modifier::ITALIC),
+            (self.blink, Modifier::BLINK),
+            (self.reverse, Modifier::REVERSE),
+            (self.hidden, Modifier::HIDDEN),
+            (self.strikethrough, Modifier::STRIKETHROUGH),
+            (self.overline, Modifier::OVERLINE),
+        ]
+    }
+}
+
+impl From<FontStyleConfig> for FontStyle {
+    fn from(config: FontStyleConfig) -> Self {
+        let modifiers = config
+            .flags()
+            .into_iter()
+            .filter(|(enabled, _)| *enabled)
+            .fold(Modifier::NONE, |modifiers, (_, modifier)| {
+                modifiers | modifier
+            });
+        let underline = match config.underline {
+            UnderlineConfig::Enabled(true) => Underline::Single,
+            UnderlineConfig::Enabled(false) => Underline::None,
+            UnderlineConfig::Style(underline) => underline,
+        };
+        Self {
+            modifiers,
             underline,
+            underline_color: config.underline_color,
+        }
+    }
+}
+
+impl From<FontStyle> for FontStyleConfig {
+    fn from(style: FontStyle) -> Self {
+        let has = |modifier| style.modifiers.contains(modifier);
+        Self {
+            bold: has(Modifier::BOLD),
+            dim: has(Modifier::DIM),
+            italic: has(Modifier::ITALIC),
+            underline: match style.underline {
+                Underline::None => UnderlineConfig::Enabled(false),
+                Underline::Single => UnderlineConfig::Enabled(true),
+                underline => UnderlineConfig::Style(underline),
+            },
+            underline_color: style.underline_color,
+            blink: has(Modifier::BLINK),
+            reverse: has(Modifier::REVERSE),
+            hidden: has(Modifier::HIDDEN),
+            strikethrough: has(Modifier::STRIKETHROUGH),
+            overline: has(Modifier::OVERLINE),
         }
     }
 }
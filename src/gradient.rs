@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::color::{from_unit, hsl_to_rgb, lerp3, oklab_to_rgb, rgb_to_hsl, rgb_to_oklab, to_unit};
+use crate::HexColor;
+
+// Define the color space in which gradient stops are interpolated
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorSpace {
+    #[default]
+    Rgb, // Straight blend of the channels, cheap but muddy between complementary colors
+    Oklab, // Perceptually uniform, keeps the brightness even along the gradient
+    Hsl,   // Walks around the hue wheel, giving the most saturated transitions
+}
+
+// Define a gradient as evenly spaced color stops
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Gradient {
+    stops: Vec<HexColor>,
+    #[serde(default)]
+    space: ColorSpace,
+}
+
+impl Gradient {
+    pub fn new(stops: Vec<HexColor>, space: ColorSpace) -> Self {
+        Self { stops, space }
+    }
+
+    pub fn stops(&self) -> &[HexColor] {
+        &self.stops
+    }
+
+    // Color at `position`, where 0.0 is the first stop and 1.0 the last one
+    pub fn at(&self, position: f64) -> HexColor {
+        self.sample(position, false)
+    }
+
+    // Color at `position` on a closed loop, blending the last stop back into the first,
+    // so a gradient running around a border has no seam
+    pub fn at_cyclic(&self, position: f64) -> HexColor {
+        self.sample(position.rem_euclid(1.0), true)
+    }
+
+    fn sample(&self, position: f64, cyclic: bool) -> HexColor {
+        let count = self.stops.len();
+        match count {
+            0 => return HexColor::WHITE,
+            1 => return self.stops[0],
+            _ => {}
+        }
+
+        let segments = if cyclic { count } else { count - 1 };
+        let scaled = position.clamp(0.0, 1.0) * segments as f64;
+        let index = (scaled.floor() as usize).min(segments - 1);
+        let from = &self.stops[index];
+        let to = &self.stops[(index + 1) % count];
+        interpolate(from, to, scaled - index as f64, self.space)
+    }
+}
+
+// Blend two stops in `space`, `amount` 0.0 giving `from` and 1.0 giving `to`
+fn interpolate(from: &HexColor, to: &HexColor, amount: f64, space: ColorSpace) -> HexColor {
+    let amount = amount.clamp(0.0, 1.0);
+    match space {
+        ColorSpace::Rgb => from.mix(to, amount),
+        ColorSpace::Oklab => from_unit(oklab_to_rgb(lerp3(
+            rgb_to_oklab(to_unit(from)),
+            rgb_to_oklab(to_unit(to)),
+            amount,
+        ))),
+        ColorSpace::Hsl => {
+            let (h1, s1, l1) = rgb_to_hsl(to_unit(from));
+            let (h2, s2, l2) = rgb_to_hsl(to_unit(to));
+            // Take the short way around the hue wheel
+            let mut delta = h2 - h1;
+            if delta > 180.0 {
+                delta -= 360.0;
+            } else if delta < -180.0 {
+                delta += 360.0;
+            }
+            from_unit(hsl_to_rgb((
+                (h1 + delta * amount).rem_euclid(360.0),
+                s1 + (s2 - s1) * amount,
+                l1 + (l2 - l1) * amount,
+            )))
+        }
+    }
+}
+
+// Fully saturated color at `hue` degrees, used for rainbow effects
+pub fn hue(hue: f64) -> HexColor {
+    from_unit(hsl_to_rgb((hue.rem_euclid(360.0), 1.0, 0.5)))
+}
@@ -0,0 +1,184 @@
+use std::io;
+
+use crate::backend::{self, Backend};
+use crate::border::Border;
+use crate::buffer::{Buffer, Cell};
+use crate::config::Config;
+use crate::contrast::ContrastCheck;
+use crate::event::Event;
+use crate::font::{FontStyle, TextAlignment};
+use crate::layout::{LayoutNode, Rect};
+use crate::link::{self, LinkHandler};
+use crate::theme::{Appearance, Theme};
+use crate::Color;
+
+// Define something the compositor can stack on top of other layers
+pub trait Layer {
+    // Area of the terminal covered by the layer
+    fn area(&self) -> Rect;
+
+    // Draw the layer; the buffer may only cover part of the area when the layer is clipped
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), io::Error>;
+
+    // Handle an input event, returning true when it was consumed
+    fn handle_event(&mut self, _event: &Event) -> bool {
+        false
+    }
+}
+
+pub struct BaseLayer {
+    pub window_size: (usize, usize),
+    pub background_color: Color,
+    pub foreground_color: Color,
+    pub border: Border,
+    pub title: Option<String>,
+    pub cursor_visibility: bool,
+    pub default_cursor_position: (usize, usize),
+    pub text_alignment: TextAlignment,
+    pub font_style: FontStyle,
+    pub layout: Option<LayoutNode>,
+    pub contrast: Option<ContrastCheck>,
+    pub appearance: Appearance, // Whether the terminal background is light or dark
+    link_handler: Option<LinkHandler>, // Called with the URL of a clicked link
+}
+
+impl BaseLayer {
+    // Create the layer for the whole terminal, with the theme matching its background
+    pub fn new() -> Result<Self, io::Error> {
+        Self::with_appearance(Appearance::detected())
+    }
+
+    fn with_appearance(appearance: Appearance) -> Result<Self, io::Error> {
+        let mut base_layer = Self::with_size(Self::get_window_size()?)?;
+        base_layer.appearance = appearance;
+        base_layer.apply_theme(&Theme::for_appearance(appearance));
+        Ok(base_layer)
+    }
+
+    // Create the layer for a window of a known size with the dark theme, without querying
+    // the terminal
+    pub fn with_size(window_size: (usize, usize)) -> Result<Self, io::Error> {
+        let theme = Theme::dark();
+        Ok(Self {
+            window_size,
+            background_color: theme.background_color,
+            foreground_color: theme.foreground_color,
+            border: Border::new()
+                .width(5)
+                .with_colors(theme.border_colors)
+                .margin(5)
+                .build()
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?,
+            title: None,
+            cursor_visibility: true,
+            default_cursor_position: (0, 0),
+            text_alignment: TextAlignment::Left,
+            font_style: FontStyle::new(false, false, false),
+            layout: None,
+            contrast: None,
+            appearance: Appearance::Dark,
+            link_handler: None,
+        })
+    }
+
+    // The terminal background is only detected when the configuration does not set it
+    pub fn from_config(config: Config) -> Result<Self, io::Error> {
+        let appearance = config.appearance.unwrap_or_else(Appearance::detected);
+        let mut base_layer = Self::with_appearance(appearance)?;
+        base_layer.apply_config(config);
+        Ok(base_layer)
+    }
+
+    // Replace the styling of the layer, e.g. after the configuration file was reloaded
+    pub fn apply_config(&mut self, config: Config) {
+        self.appearance = config.appearance.unwrap_or(self.appearance);
+        let theme = config.theme_for(self.appearance).clone();
+        if let Some(border) = config.border {
+            self.border = border;
+        }
+        self.apply_theme(&theme);
+        self.font_style = config.font_style;
+        self.text_alignment = config.text_alignment;
+        self.layout = config.layout;
+        self.contrast = config.contrast;
+    }
+
+    // Take the colors of a theme, painted under everything the layer draws, and its border
+    // palette when it has one
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        self.background_color = theme.background_color;
+        self.foreground_color = theme.foreground_color;
+        if !theme.border_colors.is_empty() {
+            self.border.set_colors(theme.border_colors.clone());
+        }
+    }
+
+    // Call `handler` with the URL of every link clicked in the layout, e.g. to open it in a
+    // browser or to navigate between screens
+    pub fn on_link(&mut self, handler: impl FnMut(&str) + 'static) {
+        self.link_handler = Some(Box::new(handler));
+    }
+
+    // Draw the border of the layer and the containers of its layout
+    pub fn render(&self) -> Result<(), io::Error> {
+        self.render_to(backend::stdout().as_mut())
+    }
+
+    pub fn render_to(&self, backend: &mut dyn Backend) -> Result<(), io::Error> {
+        let mut buffer = Buffer::new(self.area());
+        self.draw(&mut buffer)?;
+        if let Some(contrast) = &self.contrast {
+            contrast.apply(&mut buffer);
+        }
+        buffer.flush(backend)
+    }
+
+    pub fn get_window_size() -> io::Result<(usize, usize)> {
+        backend::stdout()
+            .size()
+            .map(|(w, h)| (w as usize, h as usize))
+    }
+}
+
+impl Layer for BaseLayer {
+    fn area(&self) -> Rect {
+        let (width, height) = self.window_size;
+        Rect::new(0, 0, width as u16, height as u16)
+    }
+
+    // Paint the theme colors first, so the border and layout inherit them
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), io::Error> {
+        let area = self.area();
+        let mut blank = Cell::default();
+        blank.fg = Some(self.foreground_color);
+        blank.bg = Some(self.background_color);
+        buffer.fill(area, &blank);
+        self.border.render(buffer, area)?;
+        if let Some(layout) = &self.layout {
+            layout.render(buffer, self.border.inner_rect(area))?;
+        }
+        Ok(())
+    }
+
+    // Clicks on links go to the link handler; the frame is drawn again to find the link
+    // under the mouse, as the layer keeps no frame around
+    fn handle_event(&mut self, event: &Event) -> bool {
+        if self.link_handler.is_none() {
+            return false;
+        }
+        let mut buffer = Buffer::new(self.area());
+        if self.draw(&mut buffer).is_err() {
+            return false;
+        }
+        let Some(url) = link::clicked(&buffer, event) else {
+            return false;
+        };
+        match &mut self.link_handler {
+            Some(handler) => {
+                handler(url);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::border::Border;
+use crate::buffer::Buffer;
+use crate::font::{TextAlignment, TextDirection};
+use crate::text::Text;
+
+// Define a rectangular area of the terminal, in cells
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn contains(&self, x_axis: u16, y_axis: u16) -> bool {
+        x_axis >= self.x
+            && y_axis >= self.y
+            && x_axis - self.x < self.width
+            && y_axis - self.y < self.height
+    }
+
+    // Part of the rectangle that also lies inside `other`, empty when they do not overlap
+    pub fn intersection(&self, other: Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x as u32 + self.width as u32).min(other.x as u32 + other.width as u32);
+        let bottom = (self.y as u32 + self.height as u32).min(other.y as u32 + other.height as u32);
+        Rect::new(
+            x,
+            y,
+            right.saturating_sub(x as u32) as u16,
+            bottom.saturating_sub(y as u32) as u16,
+        )
+    }
+
+    // Take cells off each edge of the rectangle, stopping at an empty rectangle
+    pub fn shrink(&self, left: usize, top: usize, right: usize, bottom: usize) -> Rect {
+        let clamp = |value: usize| value.min(u16::MAX as usize) as u16;
+        let (left, top) = (clamp(left).min(self.width), clamp(top).min(self.height));
+        Rect::new(
+            self.x.saturating_add(left),
+            self.y.saturating_add(top),
+            (self.width - left).saturating_sub(clamp(right)),
+            (self.height - top).saturating_sub(clamp(bottom)),
+        )
+    }
+
+    // Add cells around each edge of the rectangle, stopping at the edges of the coordinate
+    // space. Each edge moves independently, so a clipped left or top edge leaves the right and
+    // bottom edges where they would have been. It undoes `shrink` only when neither call had
+    // to stop at an edge
+    pub fn grow(&self, left: usize, top: usize, right: usize, bottom: usize) -> Rect {
+        let max = u16::MAX as usize;
+        let x = (self.x as usize).saturating_sub(left);
+        let y = (self.y as usize).saturating_sub(top);
+        let right = (self.x as usize + self.width as usize)
+            .saturating_add(right)
+            .min(max);
+        let bottom = (self.y as usize + self.height as usize)
+            .saturating_add(bottom)
+            .min(max);
+        Rect::new(x as u16, y as u16, (right - x) as u16, (bottom - y) as u16)
+    }
+}
+
+// Define the direction in which a container splits its area between children
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
+// Define how much of the parent's area a container claims
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Constraint {
+    Length(u16),     // Fixed number of cells
+    Percentage(u16), // Percentage of the parent's area
+    #[default]
+    Fill, // Share whatever is left with the other `Fill` siblings
+}
+
+// Define a node of the container layout tree
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutNode {
+    pub name: Option<String>,          // Name used to look the container up
+    pub direction: Direction,          // Direction in which children are laid out
+    pub constraint: Constraint,        // Size claimed inside the parent
+    pub border: Option<Border>,        // Border drawn around the container
+    pub children: Vec<LayoutNode>,     // Nested containers
+    pub collapse_borders: bool, // Let the children share their borders with each other and this one
+    pub content: Option<Text>,  // Text drawn inside the border, written as markup
+    pub text_alignment: TextAlignment, // Alignment of the lines of the content
+    pub text_direction: TextDirection, // Reading direction of the lines of the content
+    pub link_hints: bool,       // Follow links with their URL, for terminals that cannot open them
+}
+
+impl LayoutNode {
+    // Split the area between the children according to their constraints
+    pub fn split(&self, area: Rect) -> Vec<Rect> {
+        let total = match self.direction {
+            Direction::Vertical => area.height,
+            Direction::Horizontal => area.width,
+        };
+
+        let mut sizes: Vec<u16> = self
+            .children
+            .iter()
+            .map(|child| match child.constraint {
+                Constraint::Length(length) => length.min(total),
+                Constraint::Percentage(percentage) => {
+                    (total as u32 * percentage.min(100) as u32 / 100) as u16
+                }
+                Constraint::Fill => 0,
+            })
+            .collect();
+
+        let fills = self
+            .children
+            .iter()
+            .filter(|child| child.constraint == Constraint::Fill)
+            .count() as u16;
+        let used = sizes
+            .iter()
+            .fold(0u16, |sum, size| sum.saturating_add(*size));
+        let remaining = total.saturating_sub(used);
+
+        if fills > 0 {
+            let mut extra = remaining % fills;
+            for (size, child) in sizes.iter_mut().zip(&self.children) {
+                if child.constraint == Constraint::Fill {
+                    *size = remaining / fills + u16::from(extra > 0);
+                    extra = extra.saturating_sub(1);
+                }
+            }
+        }
+
+        let last = sizes.len().saturating_sub(1);
+        let mut offset = 0u16;
+        sizes
+            .into_iter()
+            .enumerate()
+            .map(|(index, size)| {
+                let size = size.min(total - offset);
+                // Collapsed siblings overlap by one cell so their borders share an edge
+                let span = if self.collapse_borders && index < last {
+                    (size + 1).min(total - offset)
+                } else {
+                    size
+                };
+                let rect = match self.direction {
+                    Direction::Vertical => Rect::new(area.x, area.y + offset, area.width, span),
+                    Direction::Horizontal => Rect::new(area.x + offset, area.y, span, area.height),
+                };
+                offset += size;
+                rect
+            })
+            .collect()
+    }
+
+    // Area shared between the children: inside the border, or on top of it when collapsing
+    fn children_area(&self, area: Rect) -> Rect {
+        match &self.border {
+            Some(border) if !self.collapse_borders => border.inner_rect(area),
+            _ => area,
+        }
+    }
+
+    // Draw the borders and the content of the container and of all its children
+    pub fn render(&self, buffer: &mut Buffer, area: Rect) -> Result<(), io::Error> {
+        if let Some(border) = &self.border {
+            border.render(buffer, area)?;
+        }
+        if let Some(content) = &self.content {
+            let inner = match &self.border {
+                Some(border) => border.inner_rect(area),
+                None => area,
+            };
+            let hinted;
+            let content = if self.link_hints {
+                hinted = content.with_link_hints();
+                &hinted
+            } else {
+                content
+            };
+            content.render(buffer, inner, self.text_alignment, self.text_direction);
+        }
+
+        let children_area = self.children_area(area);
+        for (child, child_area) in self.children.iter().zip(self.split(children_area)) {
+            child.render(buffer, child_area)?;
+        }
+        Ok(())
+    }
+
+    // Resolve the area of every named container in the tree
+    pub fn resolve(&self, area: Rect) -> Vec<(&str, Rect)> {
+        let mut resolved = Vec::new();
+        self.resolve_into(area, &mut resolved);
+        resolved
+    }
+
+    fn resolve_into<'a>(&'a self, area: Rect, resolved: &mut Vec<(&'a str, Rect)>) {
+        if let Some(name) = &self.name {
+            resolved.push((name.as_str(), area));
+        }
+
+        let children_area = self.children_area(area);
+        for (child, child_area) in self.children.iter().zip(self.split(children_area)) {
+            child.resolve_into(child_area, resolved);
+        }
+    }
+
+    // Check the layout tree, returning the path of the offending key and the reason on failure
+    pub(crate) fn validate(&self, path: &str) -> Result<(), (String, String)> {
+        if let Constraint::Percentage(percentage) = self.constraint {
+            if percentage > 100 {
+                return Err((
+                    format!("{}.constraint", path),
+                    format!("percentage {} exceeds 100", percentage),
+                ));
+            }
+        }
+
+        if let Some(border) = &self.border {
+            border.validate().map_err(|error| {
+                (
+                    format!("{}.border.{}", path, error.key()),
+                    error.to_string(),
+                )
+            })?;
+        }
+
+        let percentages: u32 = self
+            .children
+            .iter()
+            .filter_map(|child| match child.constraint {
+                Constraint::Percentage(percentage) => Some(percentage as u32),
+                _ => None,
+            })
+            .sum();
+        if percentages > 100 {
+            return Err((
+                format!("{}.children", path),
+                format!("child percentages add up to {}", percentages),
+            ));
+        }
+
+        for (index, child) in self.children.iter().enumerate() {
+            child.validate(&format!("{}.children[{}]", path, index))?;
+        }
+        Ok(())
+    }
+}
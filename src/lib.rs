@@ -0,0 +1,23 @@
+pub mod backend;
+pub mod bigtext;
+pub mod border;
+pub mod buffer;
+pub mod color;
+pub mod compositor;
+pub mod config;
+pub mod contrast;
+pub mod event;
+pub mod export;
+pub mod font;
+pub mod gradient;
+pub mod layer;
+pub mod layout;
+pub mod link;
+pub mod palette;
+pub mod record;
+pub mod symbols;
+pub mod text;
+pub mod theme;
+pub mod window;
+
+pub use crate::color::{Color, HexColor};
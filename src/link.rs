@@ -0,0 +1,92 @@
+use std::env;
+use std::sync::{OnceLock, RwLock};
+
+use crate::buffer::Buffer;
+use crate::event::{Event, MouseButton, MouseEvent, MouseEventKind};
+
+// Callback receiving the URL of a clicked link
+pub type LinkHandler = Box<dyn FnMut(&str)>;
+
+// Support forced with `set_supported`, taking precedence over the detected one
+static FORCED: RwLock<Option<bool>> = RwLock::new(None);
+
+// Terminals advertising themselves through `TERM_PROGRAM` that open OSC 8 links
+const PROGRAMS: [&str; 6] = [
+    "iTerm.app",
+    "WezTerm",
+    "vscode",
+    "ghostty",
+    "Hyper",
+    "Tabby",
+];
+
+// Terminals recognized by their `TERM` that open OSC 8 links
+const TERMS: [&str; 5] = ["kitty", "alacritty", "foot", "wezterm", "ghostty"];
+
+// Check whether the terminal turns OSC 8 sequences into clickable links. Terminal backends
+// take it as their default; for terminals without support, `Text::with_link_hints` shows
+// the URLs instead
+pub fn supported() -> bool {
+    static DETECTED: OnceLock<bool> = OnceLock::new();
+
+    let forced = match FORCED.read() {
+        Ok(forced) => *forced,
+        Err(poisoned) => *poisoned.into_inner(),
+    };
+    forced.unwrap_or_else(|| *DETECTED.get_or_init(detect))
+}
+
+// Force the support on or off, e.g. from a command line flag; None detects it again
+pub fn set_supported(supported: Option<bool>) {
+    match FORCED.write() {
+        Ok(mut forced) => *forced = supported,
+        Err(poisoned) => *poisoned.into_inner() = supported,
+    }
+}
+
+fn detect() -> bool {
+    let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+
+    // Convention shared with other command line tools to override the detection
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    if var("WT_SESSION").is_some() || var("KONSOLE_VERSION").is_some() || var("DOMTERM").is_some() {
+        return true;
+    }
+    // VTE based terminals (GNOME Terminal, Tilix, ...) support links since 0.50
+    if var("VTE_VERSION")
+        .and_then(|version| version.parse::<u32>().ok())
+        .is_some_and(|version| version >= 5000)
+    {
+        return true;
+    }
+    if var("TERM_PROGRAM").is_some_and(|program| PROGRAMS.contains(&program.as_str())) {
+        return true;
+    }
+    var("TERM").is_some_and(|term| TERMS.iter().any(|name| term.contains(name)))
+}
+
+// OSC 8 sequence making the next characters a link to `url`, or ending the link when None.
+// Control characters are dropped from the URL so it cannot end the sequence early
+pub(crate) fn osc8(url: Option<&str>) -> String {
+    let url: String = url
+        .unwrap_or_default()
+        .chars()
+        .filter(|symbol| !symbol.is_control())
+        .collect();
+    format!("\x1b]8;;{}\x1b\\", url)
+}
+
+// URL of the link under a left click, when the clicked cell of the frame has one
+pub fn clicked<'a>(buffer: &'a Buffer, event: &Event) -> Option<&'a str> {
+    match event {
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            ..
+        }) => buffer.get(*column, *row)?.link.as_deref(),
+        _ => None,
+    }
+}
@@ -1,15 +1,13 @@
-mod border;
-mod color;
-mod font;
-
 use crossterm::cursor;
 use crossterm::execute;
 use crossterm::terminal;
 use std::io::{self};
+use std::time::{Duration, Instant};
 
-use crate::border::Border;
-use crate::color::HexColor;
-use crate::font::{FontStyle, TextAlignment};
+use tixel::backend::{self, Backend, CrosstermBackend};
+use tixel::config::{Config, ConfigWatcher};
+use tixel::layer::BaseLayer;
+use tixel::record::RecordingBackend;
 
 struct CleanUp;
 
@@ -22,77 +20,87 @@ impl Drop for CleanUp {
     }
 }
 
-struct BaseLayer {
-    window_size: (usize, usize),
-    background_color: HexColor,
-    foreground_color: HexColor,
-    border: Border,
-    title: Option<String>,
-    cursor_visibility: bool,
-    default_cursor_position: (usize, usize),
-    text_alignment: TextAlignment,
-    font_style: FontStyle,
-}
+fn main() {
+    // An optional configuration file (toml, json or ron) can be passed as the first argument
+    let config_path = std::env::args().nth(1);
+    let base_layer = match &config_path {
+        Some(path) => Config::load(path)
+            .map_err(|e| e.to_string())
+            .and_then(|config| BaseLayer::from_config(config).map_err(|e| e.to_string())),
+        None => BaseLayer::new().map_err(|e| e.to_string()),
+    };
 
-impl BaseLayer {
-    fn new() -> Result<Self, io::Error> {
-        let window_size = Self::get_window_size()?;
-        Ok(Self {
-            window_size,
-            background_color: HexColor::new("#000000"),
-            foreground_color: HexColor::new("#FFFFFF"),
-            border: Border::new()
-                .width(5)
-                // .with_color(HexColor::new("#5DC5E3"))
-                .with_colors(vec![
-                    HexColor::new("#FEA837"),
-                    HexColor::new("#5DC5E3"),
-                    HexColor::new("#38761D"),
-                    HexColor::new("#A03B1E"),
-                    HexColor::new("#F7F7F7"),
-                ])
-                .padding(5)
-                .build(),
-            title: None,
-            cursor_visibility: true,
-            default_cursor_position: (0, 0),
-            text_alignment: TextAlignment::Left,
-            font_style: FontStyle::new(false, false, false),
-        })
-    }
+    let mut base_layer = match base_layer {
+        Ok(base_layer) => base_layer,
+        Err(e) => {
+            eprintln!("Failed to initialize Base Layer: {}", e);
+            return;
+        }
+    };
+    let mut watcher = config_path.map(ConfigWatcher::new);
 
-    fn get_window_size() -> io::Result<(usize, usize)> {
-        terminal::size().map(|(w, h)| (w as usize, h as usize))
-    }
-}
+    // Setting TIXEL_RECORD to a path records the session there as an asciicast file
+    let mut backend: Box<dyn Backend> = match std::env::var_os("TIXEL_RECORD") {
+        Some(path) => match RecordingBackend::create(CrosstermBackend::new(io::stdout()), path) {
+            Ok(recording) => Box::new(recording),
+            Err(e) => {
+                eprintln!("Failed to start recording: {}", e);
+                return;
+            }
+        },
+        None => backend::stdout(),
+    };
 
-fn main() {
     let _clean_up = CleanUp; // Assign the CleanUp instance to _clean_up
     terminal::enable_raw_mode().expect("Unable to enable raw mode");
     execute!(io::stdout(), terminal::EnterAlternateScreen)
         .expect("Unable to enter alternate screen");
 
-    // Create a new BaseLayer instance
-    match BaseLayer::new() {
-        Ok(base_layer) => {
-            // Render a border around the window
-            base_layer
-                .border
-                .render_box(base_layer.window_size)
-                .expect("Failed to render border");
+    // Render a border around the window
+    base_layer
+        .render_to(backend.as_mut())
+        .expect("Failed to render border");
 
-            std::thread::sleep(std::time::Duration::from_secs(3));
-            // Print the window size
-            println!(
-                "Window size: {} columns, {} rows",
-                base_layer.window_size.0, base_layer.window_size.1
-            );
+    // The last configuration change that could not be applied, reported once the terminal
+    // is restored as it would be drawn over on the alternate screen
+    let mut reload_error = None;
+    let started = Instant::now();
+    while started.elapsed() < Duration::from_secs(3) {
+        // Pick up changes made to the configuration file while running, keeping the current
+        // settings when the file is invalid
+        match watcher.as_mut().and_then(ConfigWatcher::poll) {
+            Some(Ok(config)) => {
+                reload_error = None;
+                base_layer.apply_config(config);
+                backend.clear().expect("Unable to clear the terminal");
+                base_layer
+                    .render_to(backend.as_mut())
+                    .expect("Failed to render border");
+            }
+            Some(Err(e)) => reload_error = Some(e),
+            None => {}
         }
-        Err(e) => {
-            eprintln!("Failed to initialize Base Layer: {}", e);
+        // Advance border animations with the loop
+        if base_layer.border.is_animated() {
+            base_layer.border.tick();
+            base_layer
+                .render_to(backend.as_mut())
+                .expect("Failed to render border");
         }
+        std::thread::sleep(Duration::from_millis(100));
     }
 
+    // Print the window size
+    println!(
+        "Window size: {} columns, {} rows",
+        base_layer.window_size.0, base_layer.window_size.1
+    );
+
     execute!(io::stdout(), terminal::LeaveAlternateScreen)
         .expect("Unable to leave alternate screen");
+    drop(_clean_up);
+
+    if let Some(e) = reload_error {
+        eprintln!("Failed to reload configuration: {}", e);
+    }
 }
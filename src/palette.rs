@@ -0,0 +1,294 @@
+#[cfg(any(unix, not(feature = "crossterm")))]
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use crate::HexColor;
+
+// Query answered by every terminal, sent after the others: once its answer arrives, the
+// terminal has answered everything it supports
+const DEVICE_ATTRIBUTES: &str = "\x1b[c";
+
+// Palette used to blend and export palette colors, see `Palette::install`
+static CURRENT: RwLock<Palette> = RwLock::new(Palette::xterm());
+
+// Define the colors a terminal shows for its default colors and its 256 palette entries
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Palette {
+    pub foreground: HexColor, // Color of text drawn with the default color
+    pub background: HexColor, // Color behind cells drawn with the default color
+    colors: [HexColor; 256],
+}
+
+impl Palette {
+    // The palette of xterm, which most terminals start from
+    pub const fn xterm() -> Self {
+        const BASE: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (205, 0, 0),
+            (0, 205, 0),
+            (205, 205, 0),
+            (0, 0, 238),
+            (205, 0, 205),
+            (0, 205, 205),
+            (229, 229, 229),
+            (127, 127, 127),
+            (255, 0, 0),
+            (0, 255, 0),
+            (255, 255, 0),
+            (92, 92, 255),
+            (255, 0, 255),
+            (0, 255, 255),
+            (255, 255, 255),
+        ];
+        const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+        let mut colors = [HexColor::BLACK; 256];
+        let mut index = 0;
+        while index < 256 {
+            colors[index] = if index < 16 {
+                let (r, g, b) = BASE[index];
+                HexColor::from_rgb(r, g, b)
+            } else if index < 232 {
+                // 6x6x6 color cube
+                let cube = index - 16;
+                HexColor::from_rgb(LEVELS[cube / 36], LEVELS[cube / 6 % 6], LEVELS[cube % 6])
+            } else {
+                // Grayscale ramp
+                let level = 8 + 10 * (index - 232) as u8;
+                HexColor::from_rgb(level, level, level)
+            };
+            index += 1;
+        }
+
+        Self {
+            foreground: HexColor::from_rgb(229, 229, 229),
+            background: HexColor::BLACK,
+            colors,
+        }
+    }
+
+    pub fn get(&self, index: u8) -> HexColor {
+        self.colors[index as usize]
+    }
+
+    pub fn set(&mut self, index: u8, color: HexColor) {
+        self.colors[index as usize] = color;
+    }
+
+    // Ask the terminal for its default colors (OSC 10 and 11) and its 16 named colors (OSC 4),
+    // waiting at most `timeout`. The entries the terminal does not report keep the xterm colors
+    pub fn query(timeout: Duration) -> Result<Self, io::Error> {
+        let mut request = String::from("\x1b]10;?\x07\x1b]11;?\x07");
+        for index in 0..16 {
+            request.push_str(&format!("\x1b]4;{};?\x07", index));
+        }
+        let reply = query_terminal(&request, timeout)?;
+
+        let mut palette = Self::xterm();
+        for (code, color) in parse_color_reports(&reply) {
+            match code {
+                ColorReport::Palette(index) => palette.set(index, color),
+                ColorReport::Foreground => palette.foreground = color,
+                ColorReport::Background => palette.background = color,
+            }
+        }
+        Ok(palette)
+    }
+
+    // The palette used to resolve palette colors whenever tixel needs their RGB value,
+    // e.g. to blend them or to export a frame; the xterm palette until one is installed
+    pub fn current() -> Self {
+        CURRENT
+            .read()
+            .map(|palette| palette.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+    }
+
+    // Make this palette the current one, typically after querying the terminal at startup
+    pub fn install(self) {
+        match CURRENT.write() {
+            Ok(mut current) => *current = self,
+            Err(poisoned) => *poisoned.into_inner() = self,
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::xterm()
+    }
+}
+
+// Define which color an OSC reply reports
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum ColorReport {
+    Palette(u8),
+    Foreground,
+    Background,
+}
+
+// Send queries to the controlling terminal and collect what it answers until it has
+// answered them all or `timeout` elapsed. The terminal is only read when it has something
+// to read, so a terminal that never answers leaves no pending read behind to swallow the
+// next keystroke
+#[cfg(unix)]
+pub(crate) fn query_terminal(request: &str, timeout: Duration) -> Result<String, io::Error> {
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+
+    let _raw_mode = RawMode::enable()?;
+    write!(tty, "{}{}", request, DEVICE_ATTRIBUTES)?;
+    tty.flush()?;
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    let mut chunk = [0; 256];
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        if !readable(&tty, remaining)? {
+            continue;
+        }
+        let count = tty.read(&mut chunk)?;
+        if count == 0 {
+            break;
+        }
+        reply.extend_from_slice(&chunk[..count]);
+        if device_attributes_received(&String::from_utf8_lossy(&reply)) {
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&reply).into_owned())
+}
+
+// Only Unix terminals are queried through `/dev/tty`
+#[cfg(not(unix))]
+pub(crate) fn query_terminal(_request: &str, _timeout: Duration) -> Result<String, io::Error> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+// Wait up to `timeout` for the terminal to have input, false when it had none in time or
+// the wait was interrupted by a signal
+#[cfg(unix)]
+fn readable(tty: &File, timeout: Duration) -> Result<bool, io::Error> {
+    use std::os::fd::AsRawFd;
+
+    let mut descriptor = libc::pollfd {
+        fd: tty.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+    // SAFETY: the descriptor is a single valid pollfd living for the whole call
+    match unsafe { libc::poll(&mut descriptor, 1, timeout) } {
+        -1 => match io::Error::last_os_error() {
+            error if error.kind() == io::ErrorKind::Interrupted => Ok(false),
+            error => Err(error),
+        },
+        0 => Ok(false),
+        _ => Ok(true),
+    }
+}
+
+// Whether the reply contains the answer to the device attributes query, `ESC [ ? ... c`
+fn device_attributes_received(reply: &str) -> bool {
+    reply.match_indices("\x1b[?").any(|(start, _)| {
+        let rest = &reply[start + 3..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != ';')
+            .unwrap_or(rest.len());
+        rest[end..].starts_with('c')
+    })
+}
+
+// Colors reported in OSC replies such as `ESC ] 4 ; 1 ; rgb:cdcd/0000/0000 BEL`
+pub(crate) fn parse_color_reports(reply: &str) -> Vec<(ColorReport, HexColor)> {
+    reply
+        .split("\x1b]")
+        .skip(1)
+        .filter_map(|report| {
+            let report = report.split(['\x07', '\x1b']).next()?;
+            let mut fields = report.split(';');
+            let code = match fields.next()? {
+                "4" => ColorReport::Palette(fields.next()?.parse().ok()?),
+                "10" => ColorReport::Foreground,
+                "11" => ColorReport::Background,
+                _ => return None,
+            };
+            Some((code, parse_rgb(fields.next()?)?))
+        })
+        .collect()
+}
+
+// Parse the X11 color specification `rgb:R/G/B`, each channel having 1 to 4 hex digits
+pub(crate) fn parse_rgb(spec: &str) -> Option<HexColor> {
+    let mut channels = spec.strip_prefix("rgb:")?.split('/').map(|channel| {
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = match channel.len() {
+            1..=4 => (1u32 << (4 * channel.len())) - 1,
+            _ => return None,
+        };
+        Some((value * 255 + max / 2) / max)
+    });
+
+    let mut next = || channels.next().flatten().map(|value| value as u8);
+    let color = HexColor::from_rgb(next()?, next()?, next()?);
+    Some(color)
+}
+
+// Keep the terminal in raw mode while waiting for replies, so they are neither echoed nor
+// held back until a newline, restoring the previous mode when dropped
+struct RawMode {
+    #[cfg(feature = "crossterm")]
+    was_enabled: bool,
+    #[cfg(not(feature = "crossterm"))]
+    saved: String, // Settings printed by `stty -g`
+}
+
+impl RawMode {
+    #[cfg(feature = "crossterm")]
+    fn enable() -> Result<Self, io::Error> {
+        let was_enabled = crossterm::terminal::is_raw_mode_enabled()?;
+        if !was_enabled {
+            crossterm::terminal::enable_raw_mode()?;
+        }
+        Ok(Self { was_enabled })
+    }
+
+    #[cfg(not(feature = "crossterm"))]
+    fn enable() -> Result<Self, io::Error> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        Ok(Self {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    #[cfg(feature = "crossterm")]
+    fn drop(&mut self) {
+        if !self.was_enabled {
+            let _ = crossterm::terminal::disable_raw_mode();
+        }
+    }
+
+    #[cfg(not(feature = "crossterm"))]
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+#[cfg(not(feature = "crossterm"))]
+fn stty(arguments: &[&str]) -> Result<String, io::Error> {
+    let output = std::process::Command::new("stty")
+        .args(arguments)
+        .stdin(File::open("/dev/tty")?)
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
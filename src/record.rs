@@ -0,0 +1,382 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::backend::ansi::Pen;
+use crate::backend::{AnsiBackend, Backend, TestBackend};
+use crate::buffer::Buffer;
+use crate::event::Event;
+use crate::Color;
+
+// Record what a backend shows into an asciicast v2 file, one output event per flush.
+// The frames are stored as the ANSI sequences `AnsiBackend` writes, whatever the wrapped
+// backend actually sends to the terminal, so recordings replay the same everywhere
+pub struct RecordingBackend<B: Backend, W: Write> {
+    inner: B,
+    cast: W,
+    pending: AnsiBackend<Vec<u8>>, // Output since the previous flush
+    started: Instant,
+    size: (u16, u16), // Size last written to the recording, to notice resizes
+}
+
+impl<B: Backend> RecordingBackend<B, BufWriter<File>> {
+    // Start recording into a `.cast` file, replacing it if it exists
+    pub fn create(inner: B, path: impl AsRef<Path>) -> Result<Self, io::Error> {
+        Self::new(inner, BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<B: Backend, W: Write> RecordingBackend<B, W> {
+    pub fn new(inner: B, mut cast: W) -> Result<Self, io::Error> {
+        let size = inner.size()?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        writeln!(
+            cast,
+            r#"{{"version": 2, "width": {}, "height": {}, "timestamp": {}}}"#,
+            size.0, size.1, timestamp
+        )?;
+
+        let pending = AnsiBackend::new(Vec::new(), size).links(inner.supports_links());
+        Ok(Self {
+            inner,
+            cast,
+            pending,
+            started: Instant::now(),
+            size,
+        })
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    // Stop recording, handing back the wrapped backend and the recording
+    pub fn finish(mut self) -> Result<(B, W), io::Error> {
+        self.cast.flush()?;
+        Ok((self.inner, self.cast))
+    }
+
+    fn write_event(&mut self, code: &str, data: &str) -> Result<(), io::Error> {
+        let time = self.started.elapsed().as_secs_f64();
+        let event = serde_json::to_string(&(time, code, data))?;
+        writeln!(self.cast, "{}", event)
+    }
+}
+
+impl<B: Backend, W: Write> Backend for RecordingBackend<B, W> {
+    fn draw(&mut self, buffer: &Buffer) -> Result<(), io::Error> {
+        self.pending.draw(buffer)?;
+        self.inner.draw(buffer)
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), io::Error> {
+        self.pending.hide_cursor()?;
+        self.inner.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<(), io::Error> {
+        self.pending.show_cursor()?;
+        self.inner.show_cursor()
+    }
+
+    fn set_cursor(&mut self, x_axis: u16, y_axis: u16) -> Result<(), io::Error> {
+        self.pending.set_cursor(x_axis, y_axis)?;
+        self.inner.set_cursor(x_axis, y_axis)
+    }
+
+    fn clear(&mut self) -> Result<(), io::Error> {
+        self.pending.clear()?;
+        self.inner.clear()
+    }
+
+    fn enable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        self.pending.enable_mouse_capture()?;
+        self.inner.enable_mouse_capture()
+    }
+
+    fn disable_mouse_capture(&mut self) -> Result<(), io::Error> {
+        self.pending.disable_mouse_capture()?;
+        self.inner.disable_mouse_capture()
+    }
+
+    fn size(&self) -> Result<(u16, u16), io::Error> {
+        self.inner.size()
+    }
+
+    fn supports_links(&self) -> bool {
+        self.inner.supports_links()
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        self.inner.flush()?;
+
+        let size = self.inner.size()?;
+        if size != self.size {
+            self.size = size;
+            self.write_event("r", &format!("{}x{}", size.0, size.1))?;
+        }
+
+        let output = std::mem::take(self.pending.writer_mut());
+        if !output.is_empty() {
+            self.write_event("o", &String::from_utf8_lossy(&output))?;
+        }
+        self.cast.flush()
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>, io::Error> {
+        self.inner.poll_event(timeout)
+    }
+}
+
+// Define an event of a recording
+#[derive(Clone, PartialEq, Debug)]
+pub enum CastEventKind {
+    Output(String),   // Bytes written to the terminal
+    Input(String),    // Bytes typed by the user
+    Resize(u16, u16), // The terminal now has the given number of columns and rows
+    Marker(String),   // A named point of the recording
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CastEvent {
+    pub time: f64, // Seconds since the start of the recording
+    pub kind: CastEventKind,
+}
+
+// Define an asciicast v2 recording read back from a file
+#[derive(Clone, PartialEq, Debug)]
+pub struct Recording {
+    pub width: u16,
+    pub height: u16,
+    pub events: Vec<CastEvent>,
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, io::Error> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(source: &str) -> Result<Self, io::Error> {
+        let invalid = |line: usize, message: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {}", line, message),
+            )
+        };
+        let mut lines = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, header) = lines.next().ok_or_else(|| invalid(1, "missing header"))?;
+        let header: Value = serde_json::from_str(header)?;
+        if header["version"] != 2 {
+            return Err(invalid(1, "only asciicast version 2 is supported"));
+        }
+        let dimension = |key: &str| {
+            header[key]
+                .as_u64()
+                .and_then(|value| u16::try_from(value).ok())
+                .ok_or_else(|| invalid(1, &format!("missing {}", key)))
+        };
+        let (width, height) = (dimension("width")?, dimension("height")?);
+
+        let mut events = Vec::new();
+        for (index, line) in lines {
+            let (time, code, data): (f64, String, String) = serde_json::from_str(line)?;
+            let kind = match code.as_str() {
+                "o" => CastEventKind::Output(data),
+                "i" => CastEventKind::Input(data),
+                "m" => CastEventKind::Marker(data),
+                "r" => {
+                    let size = data.split_once('x').and_then(|(columns, rows)| {
+                        Some((columns.parse().ok()?, rows.parse().ok()?))
+                    });
+                    let (columns, rows) =
+                        size.ok_or_else(|| invalid(index + 1, "malformed resize"))?;
+                    CastEventKind::Resize(columns, rows)
+                }
+                _ => continue, // Event types added by later versions of the format
+            };
+            events.push(CastEvent { time, kind });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            events,
+        })
+    }
+}
+
+// Play a recording back into a test backend, one event at a time, so every frame of an
+// animation or a resize can be checked against snapshots
+pub struct Replay<'a> {
+    recording: &'a Recording,
+    backend: TestBackend,
+    position: usize, // Index of the next event to play
+    cursor: (u16, u16),
+    pen: Pen,             // Colors and style selected by the last SGR sequences
+    link: Option<String>, // URL selected by the last OSC 8 sequence
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(recording: &'a Recording) -> Self {
+        Self {
+            recording,
+            backend: TestBackend::new(recording.width, recording.height),
+            position: 0,
+            cursor: (0, 0),
+            pen: Pen::default(),
+            link: None,
+        }
+    }
+
+    pub fn backend(&self) -> &TestBackend {
+        &self.backend
+    }
+
+    // Time of the last event played, 0.0 before the first one
+    pub fn time(&self) -> f64 {
+        self.position
+            .checked_sub(1)
+            .map_or(0.0, |index| self.recording.events[index].time)
+    }
+
+    // Play the next event that changes the screen, returning its time, or None at the end
+    pub fn step(&mut self) -> Option<f64> {
+        while let Some(event) = self.recording.events.get(self.position) {
+            self.position += 1;
+            match &event.kind {
+                CastEventKind::Output(data) => self.write(data),
+                CastEventKind::Resize(columns, rows) => self.backend.resize(*columns, *rows),
+                CastEventKind::Input(_) | CastEventKind::Marker(_) => continue,
+            }
+            return Some(event.time);
+        }
+        None
+    }
+
+    // Play every event up to and including `time`
+    pub fn seek(&mut self, time: f64) {
+        while self
+            .recording
+            .events
+            .get(self.position)
+            .is_some_and(|event| event.time <= time)
+        {
+            self.step();
+        }
+    }
+
+    pub fn finish(&mut self) {
+        while self.step().is_some() {}
+    }
+
+    // Interpret the sequences written by `AnsiBackend`, ignoring any other escape sequence
+    fn write(&mut self, data: &str) {
+        let mut text = String::new(); // Characters waiting to be split into grapheme clusters
+        let mut chars = data.chars().peekable();
+        while let Some(symbol) = chars.next() {
+            if matches!(symbol, '\x1b' | '\r' | '\n') {
+                self.print(&text);
+                text.clear();
+            }
+            match symbol {
+                '\x1b' if chars.peek() == Some(&'[') => {
+                    chars.next();
+                    let mut parameters = String::new();
+                    let mut command = None;
+                    for symbol in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&symbol) {
+                            command = Some(symbol);
+                            break;
+                        }
+                        parameters.push(symbol);
+                    }
+                    if let Some(command) = command {
+                        self.control(&parameters, command);
+                    }
+                }
+                // Operating system commands end with BEL or with ESC \
+                '\x1b' if chars.peek() == Some(&']') => {
+                    chars.next();
+                    let mut command = String::new();
+                    while let Some(symbol) = chars.next() {
+                        if symbol == '\x07' {
+                            break;
+                        }
+                        if symbol == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                        command.push(symbol);
+                    }
+                    if let Some((_, url)) = command
+                        .strip_prefix("8;")
+                        .and_then(|link| link.split_once(';'))
+                    {
+                        self.link = (!url.is_empty()).then(|| url.to_string());
+                    }
+                }
+                '\x1b' => {}
+                '\r' => self.cursor.0 = 0,
+                '\n' => self.cursor.1 = self.cursor.1.saturating_add(1),
+                _ => text.push(symbol),
+            }
+        }
+        self.print(&text);
+    }
+
+    // Write text at the cursor with the current pen, wide characters moving it two cells
+    fn print(&mut self, text: &str) {
+        for grapheme in text.graphemes(true) {
+            let (x_axis, y_axis) = self.cursor;
+            let frame = self.backend.frame_mut();
+            let width = frame.set_symbol(x_axis, y_axis, grapheme, Color::Reset);
+            for offset in 0..width {
+                if let Some(cell) = frame.get_mut(x_axis.saturating_add(offset), y_axis) {
+                    cell.fg = self.pen.fg;
+                    cell.bg = self.pen.bg;
+                    cell.style = self.pen.style;
+                    cell.link = self.link.clone();
+                }
+            }
+            self.cursor.0 = x_axis.saturating_add(width);
+        }
+    }
+
+    fn control(&mut self, parameters: &str, command: char) {
+        let numbers: Vec<u16> = parameters
+            .trim_start_matches('?')
+            .split(';')
+            .map(|number| number.parse().unwrap_or(0))
+            .collect();
+
+        match command {
+            'H' | 'f' => {
+                let row = numbers.first().copied().unwrap_or(1).max(1);
+                let column = numbers.get(1).copied().unwrap_or(1).max(1);
+                self.cursor = (column - 1, row - 1);
+                let _ = self.backend.set_cursor(column - 1, row - 1);
+            }
+            'J' if numbers.first() == Some(&2) => {
+                let _ = self.backend.clear();
+            }
+            'h' if parameters == "?25" => {
+                let _ = self.backend.show_cursor();
+            }
+            'l' if parameters == "?25" => {
+                let _ = self.backend.hide_cursor();
+            }
+            'm' => self.pen.apply_sgr(parameters),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use std::env;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Define the eight glyphs needed to draw a box: four edges and four corners
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BorderSet {
+    pub top: char,
+    pub bottom: char,
+    pub left: char,
+    pub right: char,
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+}
+
+impl BorderSet {
+    // Build a set from its edges (top, bottom, left, right) and its corners
+    // (top left, top right, bottom left, bottom right)
+    pub const fn new(edges: [char; 4], corners: [char; 4]) -> Self {
+        Self {
+            top: edges[0],
+            bottom: edges[1],
+            left: edges[2],
+            right: edges[3],
+            top_left: corners[0],
+            top_right: corners[1],
+            bottom_left: corners[2],
+            bottom_right: corners[3],
+        }
+    }
+
+    // Replace every glyph that is not plain ASCII with its counterpart from the ASCII set
+    pub fn to_ascii(self) -> Self {
+        let pick = |glyph: char, fallback: char| if glyph.is_ascii() { glyph } else { fallback };
+        Self {
+            top: pick(self.top, ASCII.top),
+            bottom: pick(self.bottom, ASCII.bottom),
+            left: pick(self.left, ASCII.left),
+            right: pick(self.right, ASCII.right),
+            top_left: pick(self.top_left, ASCII.top_left),
+            top_right: pick(self.top_right, ASCII.top_right),
+            bottom_left: pick(self.bottom_left, ASCII.bottom_left),
+            bottom_right: pick(self.bottom_right, ASCII.bottom_right),
+        }
+    }
+}
+
+pub const SOLID: BorderSet = BorderSet::new(['─', '─', '│', '│'], ['┌', '┐', '└', '┘']);
+pub const DOTTED: BorderSet = BorderSet::new(['┄', '┄', '┆', '┆'], ['┌', '┐', '└', '┘']);
+pub const DASHED: BorderSet = BorderSet::new(['┈', '┈', '┊', '┊'], ['┌', '┐', '└', '┘']);
+pub const DOUBLE: BorderSet = BorderSet::new(['═', '═', '║', '║'], ['╔', '╗', '╚', '╝']);
+pub const ROUNDED: BorderSet = BorderSet::new(['─', '─', '│', '│'], ['╭', '╮', '╰', '╯']);
+pub const THICK: BorderSet = BorderSet::new(['━', '━', '┃', '┃'], ['┏', '┓', '┗', '┛']);
+pub const ASCII: BorderSet = BorderSet::new(['-', '-', '|', '|'], ['+', '+', '+', '+']);
+pub const BLOCK: BorderSet = BorderSet::new(['▀', '▄', '█', '█'], ['█', '█', '█', '█']);
+// Half blocks drawn on the inner side of the cells, hugging the content
+pub const QUADRANT_INSIDE: BorderSet = BorderSet::new(['▄', '▀', '▐', '▌'], ['▗', '▖', '▝', '▘']);
+// Half blocks drawn on the outer side of the cells, hugging the edge of the area
+pub const QUADRANT_OUTSIDE: BorderSet = BorderSet::new(['▀', '▄', '▌', '▐'], ['▛', '▜', '▙', '▟']);
+pub const NONE: BorderSet = BorderSet::new([' ', ' ', ' ', ' '], [' ', ' ', ' ', ' ']);
+
+// Check whether the terminal locale can render box-drawing characters. Only a locale that is
+// set to something other than UTF-8 rules them out: containers, CI runners and cron jobs often
+// have no locale at all while their terminal handles Unicode fine
+pub fn supports_unicode() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+
+    *SUPPORTED.get_or_init(|| {
+        if cfg!(windows) {
+            return true;
+        }
+
+        // The first variable that is set wins, following the POSIX locale precedence
+        ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+            .map(|locale| {
+                let locale = locale.to_ascii_lowercase();
+                locale.contains("utf-8") || locale.contains("utf8")
+            })
+            .unwrap_or(true)
+    })
+}
+
+// Number of cells a grapheme cluster takes: 2 for wide characters such as CJK ideographs and
+// emoji, including ZWJ sequences that terminals draw as one emoji, 1 otherwise. Combining
+// marks belong to the cluster of their base character, and a lone zero-width character still
+// takes a cell so it cannot vanish
+pub fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width().clamp(1, 2)
+}
+
+// Number of cells taken by text, one grapheme cluster after the other
+pub fn text_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+// Define the weight of a line leaving a cell in one direction
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub enum LineWeight {
+    #[default]
+    None,
+    Light,
+    Heavy,
+    Double,
+}
+
+// Define which lines leave a cell, and how heavy each of them is
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct LineJoin {
+    pub up: LineWeight,
+    pub right: LineWeight,
+    pub down: LineWeight,
+    pub left: LineWeight,
+}
+
+impl LineJoin {
+    pub const fn new(
+        up: LineWeight,
+        right: LineWeight,
+        down: LineWeight,
+        left: LineWeight,
+    ) -> Self {
+        Self {
+            up,
+            right,
+            down,
+            left,
+        }
+    }
+
+    // Read the lines drawn by a box-drawing glyph; dashed lines and arcs count as light lines
+    pub fn from_glyph(glyph: char) -> Option<Self> {
+        let light = |up, right, down, left| {
+            let weight = |present: bool| if present { L } else { N };
+            Some(Self::new(
+                weight(up),
+                weight(right),
+                weight(down),
+                weight(left),
+            ))
+        };
+
+        match glyph {
+            '┄' | '┈' | '╌' => light(false, true, false, true),
+            '┆' | '┊' | '╎' => light(true, false, true, false),
+            '┅' | '┉' | '╍' => Some(Self::new(N, H, N, H)),
+            '┇' | '┋' | '╏' => Some(Self::new(H, N, H, N)),
+            '╭' => light(false, true, true, false),
+            '╮' => light(false, false, true, true),
+            '╯' => light(true, false, false, true),
+            '╰' => light(true, true, false, false),
+            _ => BOX_DRAWING
+                .iter()
+                .find(|(box_glyph, _)| *box_glyph == glyph)
+                .map(|(_, [up, right, down, left])| Self::new(*up, *right, *down, *left)),
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self == Self::default()
+    }
+
+    // Combine the lines of two glyphs drawn in the same cell, `other` winning where both draw
+    pub fn merge(self, other: Self) -> Self {
+        let pick = |mine: LineWeight, theirs: LineWeight| {
+            if theirs == LineWeight::None {
+                mine
+            } else {
+                theirs
+            }
+        };
+        Self::new(
+            pick(self.up, other.up),
+            pick(self.right, other.right),
+            pick(self.down, other.down),
+            pick(self.left, other.left),
+        )
+    }
+
+    // Find the glyph for the lines, or for the nearest lines Unicode has a glyph for: an axis
+    // whose two ends differ takes the weight of `prefer`, then heavy lines next to double ones
+    // become light
+    pub fn closest_glyph(self, prefer: Self) -> Option<(char, Self)> {
+        let axis = |first: LineWeight, second: LineWeight, preferred: [LineWeight; 2]| {
+            let weight = preferred
+                .into_iter()
+                .find(|weight| *weight != LineWeight::None);
+            match weight {
+                Some(weight) if first != N && second != N && first != second => (weight, weight),
+                _ => (first, second),
+            }
+        };
+
+        let mut join = self;
+        (join.up, join.down) = axis(self.up, self.down, [prefer.up, prefer.down]);
+        (join.left, join.right) = axis(self.left, self.right, [prefer.left, prefer.right]);
+
+        let lines = [join.up, join.right, join.down, join.left];
+        if lines.contains(&H) && lines.contains(&D) {
+            let lighten = |weight: LineWeight| if weight == H { L } else { weight };
+            join = Self::new(
+                lighten(join.up),
+                lighten(join.right),
+                lighten(join.down),
+                lighten(join.left),
+            );
+        }
+
+        [self, join]
+            .into_iter()
+            .find_map(|join| join.glyph().map(|glyph| (glyph, join)))
+    }
+
+    // Find the box-drawing glyph for the lines; Unicode has none for some heavy and double mixes
+    pub fn glyph(self) -> Option<char> {
+        let key = [self.up, self.right, self.down, self.left];
+        BOX_DRAWING
+            .iter()
+            .find(|(_, lines)| *lines == key)
+            .map(|(glyph, _)| *glyph)
+    }
+}
+
+const N: LineWeight = LineWeight::None;
+const L: LineWeight = LineWeight::Light;
+const H: LineWeight = LineWeight::Heavy;
+const D: LineWeight = LineWeight::Double;
+
+// Box-drawing glyphs with the lines leaving the cell, in the order up, right, down, left
+const BOX_DRAWING: [(char, [LineWeight; 4]); 109] = [
+    ('─', [N, L, N, L]),
+    ('━', [N, H, N, H]),
+    ('│', [L, N, L, N]),
+    ('┃', [H, N, H, N]),
+    ('┌', [N, L, L, N]),
+    ('┍', [N, H, L, N]),
+    ('┎', [N, L, H, N]),
+    ('┏', [N, H, H, N]),
+    ('┐', [N, N, L, L]),
+    ('┑', [N, N, L, H]),
+    ('┒', [N, N, H, L]),
+    ('┓', [N, N, H, H]),
+    ('└', [L, L, N, N]),
+    ('┕', [L, H, N, N]),
+    ('┖', [H, L, N, N]),
+    ('┗', [H, H, N, N]),
+    ('┘', [L, N, N, L]),
+    ('┙', [L, N, N, H]),
+    ('┚', [H, N, N, L]),
+    ('┛', [H, N, N, H]),
+    ('├', [L, L, L, N]),
+    ('┝', [L, H, L, N]),
+    ('┞', [H, L, L, N]),
+    ('┟', [L, L, H, N]),
+    ('┠', [H, L, H, N]),
+    ('┡', [H, H, L, N]),
+    ('┢', [L, H, H, N]),
+    ('┣', [H, H, H, N]),
+    ('┤', [L, N, L, L]),
+    ('┥', [L, N, L, H]),
+    ('┦', [H, N, L, L]),
+    ('┧', [L, N, H, L]),
+    ('┨', [H, N, H, L]),
+    ('┩', [H, N, L, H]),
+    ('┪', [L, N, H, H]),
+    ('┫', [H, N, H, H]),
+    ('┬', [N, L, L, L]),
+    ('┭', [N, L, L, H]),
+    ('┮', [N, H, L, L]),
+    ('┯', [N, H, L, H]),
+    ('┰', [N, L, H, L]),
+    ('┱', [N, L, H, H]),
+    ('┲', [N, H, H, L]),
+    ('┳', [N, H, H, H]),
+    ('┴', [L, L, N, L]),
+    ('┵', [L, L, N, H]),
+    ('┶', [L, H, N, L]),
+    ('┷', [L, H, N, H]),
+    ('┸', [H, L, N, L]),
+    ('┹', [H, L, N, H]),
+    ('┺', [H, H, N, L]),
+    ('┻', [H, H, N, H]),
+    ('┼', [L, L, L, L]),
+    ('┽', [L, L, L, H]),
+    ('┾', [L, H, L, L]),
+    ('┿', [L, H, L, H]),
+    ('╀', [H, L, L, L]),
+    ('╁', [L, L, H, L]),
+    ('╂', [H, L, H, L]),
+    ('╃', [H, L, L, H]),
+    ('╄', [H, H, L, L]),
+    ('╅', [L, L, H, H]),
+    ('╆', [L, H, H, L]),
+    ('╇', [H, H, L, H]),
+    ('╈', [L, H, H, H]),
+    ('╉', [H, L, H, H]),
+    ('╊', [H, H, H, L]),
+    ('╋', [H, H, H, H]),
+    ('═', [N, D, N, D]),
+    ('║', [D, N, D, N]),
+    ('╒', [N, D, L, N]),
+    ('╓', [N, L, D, N]),
+    ('╔', [N, D, D, N]),
+    ('╕', [N, N, L, D]),
+    ('╖', [N, N, D, L]),
+    ('╗', [N, N, D, D]),
+    ('╘', [L, D, N, N]),
+    ('╙', [D, L, N, N]),
+    ('╚', [D, D, N, N]),
+    ('╛', [L, N, N, D]),
+    ('╜', [D, N, N, L]),
+    ('╝', [D, N, N, D]),
+    ('╞', [L, D, L, N]),
+    ('╟', [D, L, D, N]),
+    ('╠', [D, D, D, N]),
+    ('╡', [L, N, L, D]),
+    ('╢', [D, N, D, L]),
+    ('╣', [D, N, D, D]),
+    ('╤', [N, D, L, D]),
+    ('╥', [N, L, D, L]),
+    ('╦', [N, D, D, D]),
+    ('╧', [L, D, N, D]),
+    ('╨', [D, L, N, L]),
+    ('╩', [D, D, N, D]),
+    ('╪', [L, D, L, D]),
+    ('╫', [D, L, D, L]),
+    ('╬', [D, D, D, D]),
+    ('╴', [N, N, N, L]),
+    ('╵', [L, N, N, N]),
+    ('╶', [N, L, N, N]),
+    ('╷', [N, N, L, N]),
+    ('╸', [N, N, N, H]),
+    ('╹', [H, N, N, N]),
+    ('╺', [N, H, N, N]),
+    ('╻', [N, N, H, N]),
+    ('╼', [N, H, N, L]),
+    ('╽', [L, N, H, N]),
+    ('╾', [N, L, N, H]),
+    ('╿', [H, N, L, N]),
+];
@@ -0,0 +1,653 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use unicode_bidi::{BidiInfo, Level};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::backend::ansi::Pen;
+use crate::buffer::Buffer;
+use crate::font::{FontStyle, Modifier, TextAlignment, TextDirection, Underline};
+use crate::layout::Rect;
+use crate::symbols;
+use crate::Color;
+
+// Columns between tab stops when expanding tabs of ANSI text
+const TAB_WIDTH: usize = 8;
+
+// Names of the modifiers accepted in markup tags
+const MODIFIERS: [(&str, Modifier); 8] = [
+    ("bold", Modifier::BOLD),
+    ("dim", Modifier::DIM),
+    ("italic", Modifier::ITALIC),
+    ("blink", Modifier::BLINK),
+    ("reverse", Modifier::REVERSE),
+    ("hidden", Modifier::HIDDEN),
+    ("strikethrough", Modifier::STRIKETHROUGH),
+    ("overline", Modifier::OVERLINE),
+];
+
+// Names of the underline shapes accepted in markup tags, e.g. `underline:curly`
+const UNDERLINES: [(&str, Underline); 5] = [
+    ("single", Underline::Single),
+    ("double", Underline::Double),
+    ("curly", Underline::Curly),
+    ("dotted", Underline::Dotted),
+    ("dashed", Underline::Dashed),
+];
+
+// Define a piece of text drawn with one set of colors and one style
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Span {
+    pub content: String,
+    pub fg: Option<Color>, // The color of the cell is replaced by the default color when None
+    pub bg: Option<Color>, // The background of the cell is kept when None
+    pub style: FontStyle,
+    pub link: Option<String>, // URL opened when the span is clicked
+}
+
+impl Span {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn fg(mut self, color: impl Into<Color>) -> Self {
+        self.fg = Some(color.into());
+        self
+    }
+
+    pub fn bg(mut self, color: impl Into<Color>) -> Self {
+        self.bg = Some(color.into());
+        self
+    }
+
+    pub fn style(mut self, style: impl Into<FontStyle>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    pub fn link(mut self, url: impl Into<String>) -> Self {
+        self.link = Some(url.into());
+        self
+    }
+
+    // Number of columns taken by the span, wide characters taking two
+    pub fn width(&self) -> usize {
+        symbols::text_width(&self.content)
+    }
+
+    fn is_plain(&self) -> bool {
+        self.fg.is_none()
+            && self.bg.is_none()
+            && self.style == FontStyle::default()
+            && self.link.is_none()
+    }
+}
+
+impl From<&str> for Span {
+    fn from(content: &str) -> Self {
+        Self::new(content)
+    }
+}
+
+impl From<String> for Span {
+    fn from(content: String) -> Self {
+        Self::new(content)
+    }
+}
+
+// Define a row of spans
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Line {
+    pub spans: Vec<Span>,
+    pub alignment: Option<TextAlignment>, // Overrides the alignment of the text when set
+}
+
+impl Line {
+    pub fn new(spans: Vec<Span>) -> Self {
+        Self {
+            spans,
+            alignment: None,
+        }
+    }
+
+    pub fn alignment(mut self, alignment: TextAlignment) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.spans.iter().map(Span::width).sum()
+    }
+
+    // Append a character, extending the last span when it has the same colors, style and link
+    fn push(&mut self, symbol: char, brush: &Brush) {
+        let Brush { pen, link } = brush;
+        match self.spans.last_mut() {
+            Some(span)
+                if span.fg == pen.fg
+                    && span.bg == pen.bg
+                    && span.style == pen.style
+                    && span.link == *link =>
+            {
+                span.content.push(symbol)
+            }
+            _ => self.spans.push(Span {
+                content: symbol.to_string(),
+                fg: pen.fg,
+                bg: pen.bg,
+                style: pen.style,
+                link: link.clone(),
+            }),
+        }
+    }
+
+    // The line for terminals that cannot open links: linked spans are underlined and followed
+    // by their URL, unless the text already is the URL
+    pub fn with_link_hints(&self) -> Line {
+        let mut spans = Vec::with_capacity(self.spans.len());
+        for span in &self.spans {
+            let Some(url) = &span.link else {
+                spans.push(span.clone());
+                continue;
+            };
+            let mut linked = span.clone();
+            if !linked.style.underline() {
+                linked.style = linked.style.with_underline(Underline::Single);
+            }
+            spans.push(linked);
+            if span.content.trim() != url {
+                spans.push(Span {
+                    content: format!(" ({})", url),
+                    style: span.style.patch(Modifier::DIM),
+                    ..span.clone()
+                });
+            }
+        }
+        Line {
+            spans,
+            alignment: self.alignment,
+        }
+    }
+
+    // Graphemes of the spans in the order they are shown, each with the span it comes from,
+    // and whether the line reads from right to left. Mixed lines are reordered with the
+    // Unicode bidirectional algorithm, and brackets inside right-to-left runs are mirrored
+    fn visual(spans: &[Span], direction: TextDirection) -> (Vec<(String, &Span)>, bool) {
+        let content: String = spans.iter().map(|span| span.content.as_str()).collect();
+        let starts: Vec<usize> = spans
+            .iter()
+            .scan(0, |start, span| {
+                let current = *start;
+                *start += span.content.len();
+                Some(current)
+            })
+            .collect();
+        let owner = |index: usize| &spans[starts.partition_point(|start| *start <= index) - 1];
+
+        let level = match direction {
+            TextDirection::Auto => None,
+            TextDirection::LeftToRight => Some(Level::ltr()),
+            TextDirection::RightToLeft => Some(Level::rtl()),
+        };
+        let info = BidiInfo::new(&content, level);
+        let rtl = match info.paragraphs.first() {
+            Some(paragraph) => paragraph.level.is_rtl(),
+            None => direction == TextDirection::RightToLeft,
+        };
+
+        if !info.has_rtl() {
+            let graphemes = content
+                .grapheme_indices(true)
+                .map(|(index, symbol)| (symbol.to_string(), owner(index)))
+                .collect();
+            return (graphemes, rtl);
+        }
+
+        let mut graphemes = Vec::new();
+        for paragraph in &info.paragraphs {
+            let (levels, runs) = info.visual_runs(paragraph, paragraph.range.clone());
+            for run in runs {
+                let start = graphemes.len();
+                graphemes.extend(
+                    content[run.clone()]
+                        .grapheme_indices(true)
+                        .map(|(index, symbol)| (symbol.to_string(), owner(run.start + index))),
+                );
+                if levels[run.start].is_rtl() {
+                    graphemes[start..].reverse();
+                    for (symbol, _) in &mut graphemes[start..] {
+                        if let Some(mirrored) = mirror(symbol) {
+                            *symbol = mirrored.to_string();
+                        }
+                    }
+                }
+            }
+        }
+        (graphemes, rtl)
+    }
+
+    // Draw the line on the first row of `area`, cut to its width without splitting a wide
+    // character. Right-to-left lines mirror the alignment and lose their left end when cut
+    pub fn render(
+        &self,
+        buffer: &mut Buffer,
+        area: Rect,
+        alignment: TextAlignment,
+        direction: TextDirection,
+    ) {
+        if area.height == 0 {
+            return;
+        }
+
+        let (mut graphemes, rtl) = Self::visual(&self.spans, direction);
+        let mut width: usize = graphemes
+            .iter()
+            .map(|(symbol, _)| symbols::grapheme_width(symbol))
+            .sum();
+        if rtl {
+            let mut cut = 0;
+            while width > area.width as usize {
+                width -= symbols::grapheme_width(&graphemes[cut].0);
+                cut += 1;
+            }
+            graphemes.drain(..cut);
+        }
+
+        let free = (area.width as usize).saturating_sub(width);
+        let offset = match (self.alignment.unwrap_or(alignment), rtl) {
+            (TextAlignment::Left, false) | (TextAlignment::Right, true) => 0,
+            (TextAlignment::Center, _) => free / 2,
+            (TextAlignment::Right, false) | (TextAlignment::Left, true) => free,
+        };
+
+        let end = area.x.saturating_add(area.width);
+        let mut x_axis = area.x.saturating_add(offset as u16);
+        for (symbol, span) in &graphemes {
+            if x_axis as usize + symbols::grapheme_width(symbol) > end as usize {
+                return;
+            }
+            let width = buffer.set_symbol(x_axis, area.y, symbol, Color::Reset);
+            for x_axis in x_axis..x_axis.saturating_add(width) {
+                if let Some(cell) = buffer.get_mut(x_axis, area.y) {
+                    cell.fg = span.fg;
+                    cell.bg = span.bg.or(cell.bg);
+                    cell.style = span.style;
+                    cell.link = span.link.clone();
+                }
+            }
+            x_axis = x_axis.saturating_add(width);
+        }
+    }
+}
+
+impl From<Vec<Span>> for Line {
+    fn from(spans: Vec<Span>) -> Self {
+        Self::new(spans)
+    }
+}
+
+impl From<Span> for Line {
+    fn from(span: Span) -> Self {
+        Self::new(vec![span])
+    }
+}
+
+impl From<&str> for Line {
+    fn from(content: &str) -> Self {
+        Span::new(content).into()
+    }
+}
+
+// Define styled text made of lines, drawn one per row. Written in configuration files
+// as markup, see `Text::from_markup`
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Text {
+    pub lines: Vec<Line>,
+}
+
+impl Text {
+    pub fn new(lines: Vec<Line>) -> Self {
+        Self { lines }
+    }
+
+    // Unstyled text, one line per line of `content`
+    pub fn raw(content: &str) -> Self {
+        Self::new(content.lines().map(Line::from).collect())
+    }
+
+    pub fn width(&self) -> usize {
+        self.lines.iter().map(Line::width).max().unwrap_or(0)
+    }
+
+    // The text for terminals that cannot open links, see `Line::with_link_hints`
+    pub fn with_link_hints(&self) -> Text {
+        Self::new(self.lines.iter().map(Line::with_link_hints).collect())
+    }
+
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    // Parse markup such as "[bold red]error[/]: message". A tag lists modifiers (bold, dim,
+    // italic, blink, reverse, hidden, strikethrough, overline), `underline` or
+    // `underline:curly` (single, double, curly, dotted, dashed), a text color and `on`
+    // followed by a background color, colors being written as in configuration files, and
+    // `link=` followed by a URL. `[/]` closes the last tag, `[/bold red]` too but only when
+    // it is the last one opened, and `[[` stands for a literal `[`
+    pub fn from_markup(markup: &str) -> Result<Self, MarkupError> {
+        let mut lines = vec![Line::default()];
+        let mut stack: Vec<(String, Brush)> = Vec::new(); // Open tags and the brush before them
+        let mut brush = Brush::default();
+        let mut chars = markup.char_indices().peekable();
+
+        while let Some((position, symbol)) = chars.next() {
+            match symbol {
+                '[' if chars.peek().map(|(_, next)| *next) == Some('[') => {
+                    chars.next();
+                    push(&mut lines, '[', &brush);
+                }
+                '[' => {
+                    let mut tag = String::new();
+                    let closed = chars.by_ref().any(|(_, symbol)| {
+                        let end = symbol == ']';
+                        if !end {
+                            tag.push(symbol);
+                        }
+                        end
+                    });
+                    if !closed {
+                        return Err(MarkupError::Unterminated { position });
+                    }
+
+                    let tag = tag.trim();
+                    if let Some(name) = tag.strip_prefix('/') {
+                        let name = name.trim();
+                        match stack.pop() {
+                            Some((opened, previous)) if name.is_empty() || name == opened => {
+                                brush = previous
+                            }
+                            _ => {
+                                return Err(MarkupError::UnexpectedClose {
+                                    tag: tag.to_string(),
+                                    position,
+                                })
+                            }
+                        }
+                    } else {
+                        let next = apply_tag(brush.clone(), tag, position)?;
+                        stack.push((tag.to_string(), brush));
+                        brush = next;
+                    }
+                }
+                '\n' => lines.push(Line::default()),
+                _ => push(&mut lines, symbol, &brush),
+            }
+        }
+        Ok(Self::new(lines))
+    }
+
+    // Markup giving back this text with `from_markup`; underline colors are left out
+    pub fn to_markup(&self) -> String {
+        let lines: Vec<String> = self
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| {
+                        let content = span.content.replace('[', "[[");
+                        if span.is_plain() {
+                            content
+                        } else {
+                            format!("[{}]{}[/]", tag_of(span), content)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    // Parse text written by other tools, keeping the colors and styles selected by its SGR
+    // sequences and the links of its OSC 8 sequences; other escape sequences are dropped and
+    // tabs are expanded to spaces
+    pub fn from_ansi(source: &str) -> Self {
+        let mut lines = vec![Line::default()];
+        let mut brush = Brush::default();
+        let mut chars = source.chars().peekable();
+
+        while let Some(symbol) = chars.next() {
+            match symbol {
+                '\x1b' => match chars.next() {
+                    Some('[') => {
+                        let mut parameters = String::new();
+                        for symbol in chars.by_ref() {
+                            if ('\x40'..='\x7e').contains(&symbol) {
+                                if symbol == 'm' {
+                                    brush.pen.apply_sgr(&parameters);
+                                }
+                                break;
+                            }
+                            parameters.push(symbol);
+                        }
+                    }
+                    // Operating system commands end with BEL or with ESC \
+                    Some(']') => {
+                        let mut command = String::new();
+                        while let Some(symbol) = chars.next() {
+                            if symbol == '\x07' {
+                                break;
+                            }
+                            if symbol == '\x1b' && chars.peek() == Some(&'\\') {
+                                chars.next();
+                                break;
+                            }
+                            command.push(symbol);
+                        }
+                        // `8 ; parameters ; URL`, an empty URL ending the link
+                        if let Some((_, url)) = command
+                            .strip_prefix("8;")
+                            .and_then(|link| link.split_once(';'))
+                        {
+                            brush.link = (!url.is_empty()).then(|| url.to_string());
+                        }
+                    }
+                    _ => {}
+                },
+                '\n' => lines.push(Line::default()),
+                '\t' => {
+                    let width = lines.last().map_or(0, Line::width);
+                    for _ in 0..TAB_WIDTH - width % TAB_WIDTH {
+                        push(&mut lines, ' ', &brush);
+                    }
+                }
+                symbol if symbol.is_control() => {}
+                symbol => push(&mut lines, symbol, &brush),
+            }
+        }
+
+        // Output of commands ends with a newline, which does not start another line
+        if lines.len() > 1 && lines.last().is_some_and(|line| line.spans.is_empty()) {
+            lines.pop();
+        }
+        Self::new(lines)
+    }
+
+    // Draw the lines from the top of `area`, dropping the ones that do not fit. With
+    // `TextDirection::Auto`, each line takes the direction of its first letter
+    pub fn render(
+        &self,
+        buffer: &mut Buffer,
+        area: Rect,
+        alignment: TextAlignment,
+        direction: TextDirection,
+    ) {
+        for (line, y_axis) in self.lines.iter().zip(area.y..area.y + area.height) {
+            let row = Rect::new(area.x, y_axis, area.width, 1);
+            line.render(buffer, row, alignment, direction);
+        }
+    }
+}
+
+impl From<&str> for Text {
+    fn from(content: &str) -> Self {
+        Self::raw(content)
+    }
+}
+
+impl From<Line> for Text {
+    fn from(line: Line) -> Self {
+        Self::new(vec![line])
+    }
+}
+
+impl From<Vec<Line>> for Text {
+    fn from(lines: Vec<Line>) -> Self {
+        Self::new(lines)
+    }
+}
+
+impl TryFrom<String> for Text {
+    type Error = MarkupError;
+
+    fn try_from(markup: String) -> Result<Self, Self::Error> {
+        Self::from_markup(&markup)
+    }
+}
+
+impl From<Text> for String {
+    fn from(text: Text) -> Self {
+        text.to_markup()
+    }
+}
+
+// Define the errors that can occur while parsing markup, with the byte offset of the tag
+#[derive(Debug, PartialEq, Eq)]
+pub enum MarkupError {
+    Unterminated { position: usize }, // A tag has no closing `]`
+    UnknownStyle { word: String, position: usize }, // A tag word is neither a style nor a color
+    UnexpectedClose { tag: String, position: usize }, // A closing tag is not the last one opened
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unterminated { position } => {
+                write!(f, "tag at offset {} is missing its closing `]`", position)
+            }
+            Self::UnknownStyle { word, position } => {
+                write!(f, "unknown style `{}` in tag at offset {}", word, position)
+            }
+            Self::UnexpectedClose { tag, position } => write!(
+                f,
+                "`[{}]` at offset {} does not close the last open tag",
+                tag, position
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+// Define what the parsers draw the next characters with
+#[derive(Clone, Default)]
+struct Brush {
+    pen: Pen,
+    link: Option<String>,
+}
+
+// Bracket shown in place of another inside right-to-left text, so that an opening bracket
+// still faces the text it encloses
+fn mirror(symbol: &str) -> Option<char> {
+    const PAIRS: [(char, char); 6] = [
+        ('(', ')'),
+        ('[', ']'),
+        ('{', '}'),
+        ('<', '>'),
+        ('«', '»'),
+        ('‹', '›'),
+    ];
+
+    let mut chars = symbol.chars();
+    let (Some(symbol), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    PAIRS.iter().find_map(|&(open, close)| {
+        if symbol == open {
+            Some(close)
+        } else if symbol == close {
+            Some(open)
+        } else {
+            None
+        }
+    })
+}
+
+fn push(lines: &mut [Line], symbol: char, brush: &Brush) {
+    if let Some(line) = lines.last_mut() {
+        line.push(symbol, brush);
+    }
+}
+
+// Brush after the words of an opening tag
+fn apply_tag(mut brush: Brush, tag: &str, position: usize) -> Result<Brush, MarkupError> {
+    let unknown = |word: &str| MarkupError::UnknownStyle {
+        word: word.to_string(),
+        position,
+    };
+    let mut words = tag.split_whitespace();
+
+    while let Some(word) = words.next() {
+        if let Some((_, modifier)) = MODIFIERS.iter().find(|(name, _)| *name == word) {
+            brush.pen.style = brush.pen.style.patch(*modifier);
+        } else if word == "underline" {
+            brush.pen.style = brush.pen.style.with_underline(Underline::Single);
+        } else if let Some(shape) = word.strip_prefix("underline:") {
+            let (_, underline) = UNDERLINES
+                .iter()
+                .find(|(name, _)| *name == shape)
+                .ok_or_else(|| unknown(word))?;
+            brush.pen.style = brush.pen.style.with_underline(*underline);
+        } else if let Some(url) = word.strip_prefix("link=") {
+            brush.link = Some(url.to_string());
+        } else if word == "on" {
+            let color = words.next().ok_or_else(|| unknown(word))?;
+            brush.pen.bg = Some(color.parse().map_err(|_| unknown(color))?);
+        } else {
+            brush.pen.fg = Some(word.parse().map_err(|_| unknown(word))?);
+        }
+    }
+    Ok(brush)
+}
+
+// Words of the tag giving a span its colors and style
+fn tag_of(span: &Span) -> String {
+    let mut words: Vec<String> = MODIFIERS
+        .iter()
+        .filter(|(_, modifier)| span.style.contains(*modifier))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    match span.style.underline_style() {
+        Underline::None => {}
+        Underline::Single => words.push("underline".into()),
+        shape => {
+            if let Some((name, _)) = UNDERLINES.iter().find(|(_, underline)| *underline == shape) {
+                words.push(format!("underline:{}", name));
+            }
+        }
+    }
+    if let Some(fg) = span.fg {
+        words.push(fg.to_string());
+    }
+    if let Some(bg) = span.bg {
+        words.push(format!("on {}", bg));
+    }
+    if let Some(url) = &span.link {
+        words.push(format!("link={}", url));
+    }
+    words.join(" ")
+}
@@ -0,0 +1,122 @@
+use std::env;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::palette::{self, ColorReport, Palette};
+use crate::{Color, HexColor};
+
+// How long the terminal gets to report its background color
+const DETECT_TIMEOUT: Duration = Duration::from_millis(100);
+
+// Define whether the terminal shows dark text on a light background or the opposite
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Appearance {
+    Light,
+    #[default]
+    Dark,
+}
+
+impl Appearance {
+    // Classify a background color
+    pub fn of(background: &HexColor) -> Self {
+        if background.is_dark() {
+            Self::Dark
+        } else {
+            Self::Light
+        }
+    }
+
+    // Ask the terminal for its background color, falling back to `COLORFGBG`, then to dark
+    pub fn detect(timeout: Duration) -> Self {
+        Self::query(timeout)
+            .or_else(Self::from_env)
+            .unwrap_or_default()
+    }
+
+    // Appearance of the terminal, detected on the first call only, as the terminal may take
+    // up to 100ms to answer and does not change its background while running
+    pub fn detected() -> Self {
+        static DETECTED: OnceLock<Appearance> = OnceLock::new();
+
+        *DETECTED.get_or_init(|| Self::detect(DETECT_TIMEOUT))
+    }
+
+    // Appearance of the background reported by the terminal (OSC 11), None when the
+    // terminal did not answer within `timeout`
+    pub fn query(timeout: Duration) -> Option<Self> {
+        let reply = palette::query_terminal("\x1b]11;?\x07", timeout).ok()?;
+        palette::parse_color_reports(&reply)
+            .into_iter()
+            .find(|(report, _)| *report == ColorReport::Background)
+            .map(|(_, background)| Self::of(&background))
+    }
+
+    // Appearance advertised by `COLORFGBG`, e.g. "15;0", set by rxvt, Konsole and others;
+    // its last field is the palette index of the background
+    pub fn from_env() -> Option<Self> {
+        let value = env::var("COLORFGBG").ok()?;
+        let index: u8 = value.rsplit(';').next()?.parse().ok()?;
+        Some(Self::of(&Palette::current().get(index)))
+    }
+}
+
+// Colors applied to the base layer and inherited by containers
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub background_color: Color, // Color painted behind every cell
+    pub foreground_color: Color, // Color used for text
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub border_colors: Vec<Color>, // Colors of the border layers, empty to keep those of the border
+}
+
+impl Theme {
+    // Light text and a bright border on a black background
+    pub fn dark() -> Self {
+        Self {
+            border_colors: vec![
+                HexColor::new("#FEA837").into(),
+                HexColor::new("#5DC5E3").into(),
+                HexColor::new("#38761D").into(),
+                HexColor::new("#A03B1E").into(),
+                HexColor::new("#F7F7F7").into(),
+            ],
+            ..Self::default()
+        }
+    }
+
+    // Dark text and a deeper border on a white background
+    pub fn light() -> Self {
+        Self {
+            background_color: HexColor::WHITE.into(),
+            foreground_color: HexColor::BLACK.into(),
+            border_colors: vec![
+                HexColor::new("#B35C00").into(),
+                HexColor::new("#1F7A99").into(),
+                HexColor::new("#2A5A16").into(),
+                HexColor::new("#A03B1E").into(),
+                HexColor::new("#3A3A3A").into(),
+            ],
+        }
+    }
+
+    pub fn for_appearance(appearance: Appearance) -> Self {
+        match appearance {
+            Appearance::Light => Self::light(),
+            Appearance::Dark => Self::dark(),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background_color: HexColor::BLACK.into(),
+            foreground_color: HexColor::WHITE.into(),
+            border_colors: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,618 @@
+use std::io;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::border::Border;
+use crate::buffer::{Buffer, Cell};
+use crate::event::{Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use crate::font::FontStyle;
+use crate::layer::Layer;
+use crate::layout::{LayoutNode, Rect};
+use crate::symbols;
+use crate::{Color, HexColor};
+
+// Buttons drawn at the right end of the title bar: minimize, then maximize / restore
+const BUTTONS: &str = "[-][+]";
+const BUTTON_WIDTH: u16 = 3;
+
+// Define the states a floating window can be in
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WindowState {
+    #[default]
+    Normal, // Drawn at its own position and size
+    Minimized, // Only shown in the task bar
+    Maximized, // Fills the desktop above the task bar
+}
+
+// Handle returned when a window is opened, used to address it afterwards
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct WindowId(usize);
+
+// Define a floating, bordered window
+pub struct Window {
+    pub title: String,
+    pub border: Border,
+    pub background_color: Option<Color>, // Fill behind the content, the terminal default when None
+    pub layout: Option<LayoutNode>,      // Containers drawn inside the border
+    area: Rect,                          // Position and size while in the normal state
+    state: WindowState,
+}
+
+impl Window {
+    pub fn new(title: impl Into<String>, area: Rect, border: Border) -> Self {
+        Self {
+            title: title.into(),
+            border,
+            background_color: None,
+            layout: None,
+            area,
+            state: WindowState::Normal,
+        }
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    pub fn state(&self) -> WindowState {
+        self.state
+    }
+
+    // Smallest size keeping the title bar and one cell of content
+    fn min_size(&self) -> (u16, u16) {
+        let (width, height) = self.border.frame_size();
+        let width = (width + 1).max(BUTTONS.len() + 2);
+        (
+            width.min(u16::MAX as usize) as u16,
+            (height + 1).min(u16::MAX as usize) as u16,
+        )
+    }
+
+    fn draw(&self, buffer: &mut Buffer, area: Rect, focused: bool) -> Result<(), io::Error> {
+        // Clear the area first so nothing below the window shows through; the cleared cells
+        // are drawn, so they are flushed even without a background color
+        let mut blank = Cell::default();
+        blank.bg = self.background_color;
+        buffer.fill(area, &blank);
+
+        self.border.render(buffer, area)?;
+        if let Some(layout) = &self.layout {
+            layout.render(buffer, self.border.inner_rect(area))?;
+        }
+
+        // Write the title and the buttons over the top border line, in the color of the line
+        let bar = self.border.border_rect(area);
+        if bar.width < 2 || bar.height == 0 {
+            return Ok(());
+        }
+        let color = buffer
+            .get(bar.x, bar.y)
+            .and_then(|cell| cell.fg)
+            .unwrap_or_else(|| HexColor::WHITE.into());
+        let style = FontStyle::new(focused, false, false);
+
+        let buttons_x = (bar.x + bar.width - 1).saturating_sub(BUTTONS.len() as u16);
+        let title_width = buttons_x.saturating_sub(bar.x + 2) as usize;
+        let title = format!(" {} ", self.title);
+        write(
+            buffer,
+            (bar.x + 1, bar.y),
+            &title,
+            title_width,
+            &color,
+            style,
+        );
+        write(
+            buffer,
+            (buttons_x, bar.y),
+            BUTTONS,
+            BUTTONS.len(),
+            &color,
+            style,
+        );
+        Ok(())
+    }
+}
+
+// Define what the mouse is currently dragging
+#[derive(Clone, Copy)]
+enum Drag {
+    Move { id: WindowId, grab: (u16, u16) }, // Grabbed at an offset from the top left corner
+    Resize { id: WindowId, edges: Edges },
+}
+
+impl Drag {
+    fn id(self) -> WindowId {
+        match self {
+            Drag::Move { id, .. } | Drag::Resize { id, .. } => id,
+        }
+    }
+}
+
+// Define the edges of a window that follow the mouse while resizing
+#[derive(Clone, Copy, Default)]
+struct Edges {
+    left: bool,
+    right: bool,
+    top: bool,
+    bottom: bool,
+}
+
+// Define what lies under the mouse within a window
+enum Hit {
+    TitleBar,
+    Minimize,
+    Maximize,
+    Edges(Edges),
+    Inside,
+}
+
+// Manage the floating windows of the desktop and the task bar along its bottom row.
+// Keyboard equivalents act on the focused window:
+//   Alt+Arrows        move by one cell
+//   Alt+Shift+Arrows  resize by one cell
+//   Alt+N             minimize
+//   Alt+M             maximize / restore
+//   Alt+W             focus the next window of the window list
+//   Alt+1 .. Alt+9    focus a window by its place in the window list
+pub struct WindowManager {
+    area: Rect,                       // Desktop the windows live in, task bar included
+    windows: Vec<(WindowId, Window)>, // Window list, in the order the windows were opened
+    stack: Vec<WindowId>,             // Stacking order, the focused window last
+    drag: Option<Drag>,
+    next_id: usize,
+}
+
+impl WindowManager {
+    pub fn new(area: Rect) -> Self {
+        Self {
+            area,
+            windows: Vec::new(),
+            stack: Vec::new(),
+            drag: None,
+            next_id: 0,
+        }
+    }
+
+    pub fn resize(&mut self, area: Rect) {
+        self.area = area;
+    }
+
+    // Open a window on top of the others and focus it
+    pub fn open(&mut self, window: Window) -> WindowId {
+        let id = WindowId(self.next_id);
+        self.next_id += 1;
+        self.windows.push((id, window));
+        self.stack.push(id);
+        id
+    }
+
+    pub fn close(&mut self, id: WindowId) -> Option<Window> {
+        self.stack.retain(|other| *other != id);
+        if self.drag.is_some_and(|drag| drag.id() == id) {
+            self.drag = None;
+        }
+        let index = self.windows.iter().position(|(other, _)| *other == id)?;
+        Some(self.windows.remove(index).1)
+    }
+
+    pub fn get(&self, id: WindowId) -> Option<&Window> {
+        self.windows
+            .iter()
+            .find(|(other, _)| *other == id)
+            .map(|(_, window)| window)
+    }
+
+    pub fn get_mut(&mut self, id: WindowId) -> Option<&mut Window> {
+        self.windows
+            .iter_mut()
+            .find(|(other, _)| *other == id)
+            .map(|(_, window)| window)
+    }
+
+    // The window list, in the order the windows were opened
+    pub fn windows(&self) -> impl Iterator<Item = (WindowId, &Window)> {
+        self.windows.iter().map(|(id, window)| (*id, window))
+    }
+
+    // The topmost window that is not minimized
+    pub fn focused(&self) -> Option<WindowId> {
+        self.stack.iter().rev().copied().find(|id| {
+            self.get(*id)
+                .is_some_and(|window| window.state != WindowState::Minimized)
+        })
+    }
+
+    // Raise a window above the others, restoring it first when it is minimized
+    pub fn focus(&mut self, id: WindowId) {
+        let Some(window) = self.get_mut(id) else {
+            return;
+        };
+        if window.state == WindowState::Minimized {
+            window.state = WindowState::Normal;
+        }
+        self.stack.retain(|other| *other != id);
+        self.stack.push(id);
+    }
+
+    // Focus the window after the focused one in the window list, wrapping around
+    pub fn focus_next(&mut self) {
+        let index = self
+            .focused()
+            .and_then(|id| self.windows.iter().position(|(other, _)| *other == id))
+            .map_or(0, |index| index + 1);
+        if let Some((id, _)) = self.windows.get(index % self.windows.len().max(1)) {
+            self.focus(*id);
+        }
+    }
+
+    pub fn minimize(&mut self, id: WindowId) {
+        self.set_state(id, WindowState::Minimized);
+    }
+
+    pub fn maximize(&mut self, id: WindowId) {
+        self.set_state(id, WindowState::Maximized);
+        self.focus(id);
+    }
+
+    pub fn restore(&mut self, id: WindowId) {
+        self.set_state(id, WindowState::Normal);
+        self.focus(id);
+    }
+
+    pub fn toggle_maximize(&mut self, id: WindowId) {
+        match self.get(id).map(Window::state) {
+            Some(WindowState::Maximized) => self.restore(id),
+            Some(_) => self.maximize(id),
+            None => {}
+        }
+    }
+
+    // Move a window, keeping it inside the desktop
+    pub fn move_to(&mut self, id: WindowId, x_axis: u16, y_axis: u16) {
+        let desktop = self.desktop();
+        let Some(window) = self.get_mut(id) else {
+            return;
+        };
+        let right = (desktop.x + desktop.width).saturating_sub(window.area.width);
+        let bottom = (desktop.y + desktop.height).saturating_sub(window.area.height);
+        window.area.x = x_axis.clamp(desktop.x, right.max(desktop.x));
+        window.area.y = y_axis.clamp(desktop.y, bottom.max(desktop.y));
+    }
+
+    pub fn move_by(&mut self, id: WindowId, columns: i32, rows: i32) {
+        if let Some(area) = self.get(id).map(Window::area) {
+            let x_axis = (area.x as i32 + columns).max(0) as u16;
+            let y_axis = (area.y as i32 + rows).max(0) as u16;
+            self.move_to(id, x_axis, y_axis);
+        }
+    }
+
+    // Change the size of a window, between its minimum size and the desktop
+    pub fn set_size(&mut self, id: WindowId, width: u16, height: u16) {
+        let desktop = self.desktop();
+        let Some(window) = self.get_mut(id) else {
+            return;
+        };
+        let (min_width, min_height) = window.min_size();
+        let max_width = (desktop.x + desktop.width).saturating_sub(window.area.x);
+        let max_height = (desktop.y + desktop.height).saturating_sub(window.area.y);
+        window.area.width = width.min(max_width).max(min_width);
+        window.area.height = height.min(max_height).max(min_height);
+    }
+
+    pub fn resize_by(&mut self, id: WindowId, columns: i32, rows: i32) {
+        if let Some(area) = self.get(id).map(Window::area) {
+            let width = (area.width as i32 + columns).clamp(0, u16::MAX as i32) as u16;
+            let height = (area.height as i32 + rows).clamp(0, u16::MAX as i32) as u16;
+            self.set_size(id, width, height);
+        }
+    }
+
+    fn set_state(&mut self, id: WindowId, state: WindowState) {
+        if let Some(window) = self.get_mut(id) {
+            window.state = state;
+        }
+    }
+
+    // Area the windows may cover: the desktop without the task bar
+    fn desktop(&self) -> Rect {
+        self.area.shrink(0, 0, 0, 1)
+    }
+
+    // Area a window currently covers, None while minimized
+    fn frame(&self, window: &Window) -> Option<Rect> {
+        match window.state {
+            WindowState::Normal => Some(window.area),
+            WindowState::Minimized => None,
+            WindowState::Maximized => Some(self.desktop()),
+        }
+    }
+
+    // Title and column span of every entry in the task bar, following the window list
+    fn task_bar(&self) -> Vec<(WindowId, u16, String)> {
+        let mut x_axis = self.area.x;
+        self.windows
+            .iter()
+            .map(|(id, window)| {
+                let label = format!(" {} ", window.title);
+                let entry = (*id, x_axis, label);
+                x_axis = x_axis.saturating_add(symbols::text_width(&entry.2) as u16 + 1);
+                entry
+            })
+            .collect()
+    }
+
+    fn hit(&self, window: &Window, frame: Rect, column: u16, row: u16) -> Hit {
+        let bar = window.border.border_rect(frame);
+        if !bar.contains(column, row) {
+            return Hit::Inside;
+        }
+
+        let right = bar.x + bar.width - 1;
+        let bottom = bar.y + bar.height - 1;
+        let edges = Edges {
+            left: column == bar.x,
+            right: column == right,
+            top: row == bar.y && (column == bar.x || column == right),
+            bottom: row == bottom,
+        };
+        if edges.left || edges.right || edges.bottom || edges.top {
+            return Hit::Edges(edges);
+        }
+        if row != bar.y {
+            return Hit::Inside;
+        }
+
+        let buttons_x = right.saturating_sub(BUTTONS.len() as u16);
+        match column.checked_sub(buttons_x) {
+            Some(offset) if offset < BUTTON_WIDTH => Hit::Minimize,
+            Some(offset) if offset < 2 * BUTTON_WIDTH => Hit::Maximize,
+            _ => Hit::TitleBar,
+        }
+    }
+
+    fn handle_mouse(&mut self, event: &MouseEvent) -> bool {
+        let (column, row) = (event.column, event.row);
+
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                // The task bar switches between the windows
+                if row == self.area.y + self.area.height.saturating_sub(1) {
+                    let entry = self.task_bar().into_iter().find(|(_, x_axis, label)| {
+                        (*x_axis..x_axis + symbols::text_width(label) as u16).contains(&column)
+                    });
+                    return match entry {
+                        Some((id, _, _)) if self.focused() == Some(id) => {
+                            self.minimize(id);
+                            true
+                        }
+                        Some((id, _, _)) => {
+                            self.focus(id);
+                            true
+                        }
+                        None => false,
+                    };
+                }
+
+                let target = self.stack.iter().rev().find_map(|id| {
+                    let window = self.get(*id)?;
+                    let frame = self.frame(window)?;
+                    frame
+                        .contains(column, row)
+                        .then(|| (*id, frame, self.hit(window, frame, column, row)))
+                });
+                let Some((id, frame, hit)) = target else {
+                    return false;
+                };
+
+                self.focus(id);
+                let maximized = self.get(id).map(Window::state) == Some(WindowState::Maximized);
+                match hit {
+                    Hit::Minimize => self.minimize(id),
+                    Hit::Maximize => self.toggle_maximize(id),
+                    Hit::TitleBar if !maximized => {
+                        self.drag = Some(Drag::Move {
+                            id,
+                            grab: (column - frame.x, row - frame.y),
+                        })
+                    }
+                    Hit::Edges(edges) if !maximized => self.drag = Some(Drag::Resize { id, edges }),
+                    _ => {}
+                }
+                true
+            }
+            MouseEventKind::Drag(MouseButton::Left) => match self.drag {
+                Some(Drag::Move { id, grab }) => {
+                    self.move_to(
+                        id,
+                        column.saturating_sub(grab.0),
+                        row.saturating_sub(grab.1),
+                    );
+                    true
+                }
+                Some(Drag::Resize { id, edges }) => {
+                    self.drag_edges(id, edges, column, row);
+                    true
+                }
+                None => false,
+            },
+            MouseEventKind::Up(MouseButton::Left) => self.drag.take().is_some(),
+            _ => false,
+        }
+    }
+
+    // Follow the mouse with the grabbed edges, keeping the opposite edges in place
+    fn drag_edges(&mut self, id: WindowId, edges: Edges, column: u16, row: u16) {
+        let desktop = self.desktop();
+        let Some(window) = self.get_mut(id) else {
+            return;
+        };
+        let area = window.area;
+        let (min_width, min_height) = window.min_size();
+
+        let (x_axis, width) = drag_axis(
+            (area.x, area.width),
+            (edges.left, edges.right),
+            column,
+            min_width,
+            (desktop.x, desktop.x + desktop.width),
+        );
+        let (y_axis, height) = drag_axis(
+            (area.y, area.height),
+            (edges.top, edges.bottom),
+            row,
+            min_height,
+            (desktop.y, desktop.y + desktop.height),
+        );
+        window.area = Rect::new(x_axis, y_axis, width, height);
+    }
+
+    fn handle_key(&mut self, event: &KeyEvent) -> bool {
+        if !event.modifiers.alt {
+            return false;
+        }
+        let shift = event.modifiers.shift;
+
+        if let KeyCode::Char(digit @ '1'..='9') = event.code {
+            let index = digit as usize - '1' as usize;
+            return match self.windows.get(index) {
+                Some((id, _)) => {
+                    self.focus(*id);
+                    true
+                }
+                None => false,
+            };
+        }
+        if event.code == KeyCode::Char('w') {
+            self.focus_next();
+            return true;
+        }
+
+        let Some(id) = self.focused() else {
+            return false;
+        };
+        let step = match event.code {
+            KeyCode::Left => (-1, 0),
+            KeyCode::Right => (1, 0),
+            KeyCode::Up => (0, -1),
+            KeyCode::Down => (0, 1),
+            KeyCode::Char('n') => {
+                self.minimize(id);
+                return true;
+            }
+            KeyCode::Char('m') => {
+                self.toggle_maximize(id);
+                return true;
+            }
+            _ => return false,
+        };
+
+        if self.get(id).map(Window::state) == Some(WindowState::Maximized) {
+            return true;
+        }
+        if shift {
+            self.resize_by(id, step.0, step.1);
+        } else {
+            self.move_by(id, step.0, step.1);
+        }
+        true
+    }
+}
+
+impl Layer for WindowManager {
+    fn area(&self) -> Rect {
+        self.area
+    }
+
+    // The desktop is transparent, so the layers below show around the windows, and the
+    // cells a window moved away from are drawn again
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), io::Error> {
+        buffer.fill(self.area, &Cell::transparent());
+        let focused = self.focused();
+        for id in &self.stack {
+            let Some(window) = self.get(*id) else {
+                continue;
+            };
+            if let Some(frame) = self.frame(window) {
+                window.draw(buffer, frame, focused == Some(*id))?;
+            }
+        }
+
+        // The focused window is shown in bold, minimized ones dimmed
+        let row = self.area.y + self.area.height.saturating_sub(1);
+        let end = self.area.x + self.area.width;
+        for (id, x_axis, label) in self.task_bar() {
+            let Some(window) = self.get(id) else {
+                continue;
+            };
+            let color: Color = if window.state == WindowState::Minimized {
+                HexColor::WHITE.mix(&HexColor::BLACK, 0.5).into()
+            } else {
+                HexColor::WHITE.into()
+            };
+            let style = FontStyle::new(focused == Some(id), false, false);
+            let width = end.saturating_sub(x_axis) as usize;
+            write(buffer, (x_axis, row), &label, width, &color, style);
+        }
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Mouse(mouse) => self.handle_mouse(mouse),
+            Event::Key(key) => self.handle_key(key),
+            _ => false,
+        }
+    }
+}
+
+// Start and length of a window along one axis once its grabbed start or end edge follows the
+// mouse to `position`, the other edge staying put. The window keeps at least `min` cells and
+// stays between `bounds`
+fn drag_axis(
+    (start, length): (u16, u16),
+    (grab_start, grab_end): (bool, bool),
+    position: u16,
+    min: u16,
+    (low, high): (u16, u16),
+) -> (u16, u16) {
+    let end = start.saturating_add(length);
+    if grab_start {
+        let start = position.max(low).min(end.saturating_sub(min));
+        (start, end - start)
+    } else if grab_end {
+        let end = position
+            .saturating_add(1)
+            .min(high)
+            .max(start.saturating_add(min));
+        (start, end - start)
+    } else {
+        (start, length)
+    }
+}
+
+// Write a line of text, cut to `width` cells without splitting a wide character
+fn write(
+    buffer: &mut Buffer,
+    (x_axis, y_axis): (u16, u16),
+    text: &str,
+    width: usize,
+    color: &Color,
+    style: FontStyle,
+) {
+    let mut offset = 0;
+    for symbol in text.graphemes(true) {
+        if offset + symbols::grapheme_width(symbol) > width {
+            break;
+        }
+        let x_axis = x_axis.saturating_add(offset as u16);
+        let written = buffer.set_symbol(x_axis, y_axis, symbol, color);
+        for x_axis in x_axis..x_axis.saturating_add(written) {
+            if let Some(cell) = buffer.get_mut(x_axis, y_axis) {
+                cell.style = style;
+            }
+        }
+        offset += written as usize;
+    }
+}
@@ -0,0 +1,98 @@
+use tixel::backend::{AnsiBackend, Backend};
+use tixel::buffer::Buffer;
+use tixel::export;
+use tixel::font::{FontStyle, Modifier, Underline};
+use tixel::layout::Rect;
+use tixel::Color;
+
+// A row with one cell per entry, holding the letters from "a" on
+fn row(cells: &[(Option<Color>, FontStyle)]) -> Buffer {
+    let mut buffer = Buffer::new(Rect::new(0, 0, cells.len() as u16, 1));
+    for (index, (fg, style)) in cells.iter().enumerate() {
+        let cell = buffer
+            .get_mut(index as u16, 0)
+            .expect("cell inside the row");
+        cell.symbol = char::from(b'a' + index as u8).to_string();
+        cell.fg = *fg;
+        cell.style = *style;
+    }
+    buffer
+}
+
+fn style(modifiers: Modifier) -> FontStyle {
+    modifiers.into()
+}
+
+#[test]
+fn only_the_modifiers_that_change_are_written() {
+    let ansi = export::to_ansi(
+        &row(&[
+            (None, style(Modifier::BOLD)),
+            (None, style(Modifier::BOLD | Modifier::ITALIC)),
+            (None, style(Modifier::ITALIC)),
+            (None, style(Modifier::ITALIC | Modifier::STRIKETHROUGH)),
+            (None, style(Modifier::ITALIC | Modifier::STRIKETHROUGH)),
+            (None, FontStyle::default()),
+        ]),
+        false,
+    );
+
+    assert_eq!(ansi, "\x1b[1ma\x1b[3mb\x1b[22mc\x1b[9mde\x1b[23;29mf\n");
+}
+
+#[test]
+fn bold_and_dim_are_turned_off_together() {
+    let ansi = export::to_ansi(
+        &row(&[
+            (None, style(Modifier::BOLD | Modifier::DIM)),
+            (None, style(Modifier::DIM)),
+            (None, style(Modifier::BOLD)),
+        ]),
+        false,
+    );
+
+    // The attribute that stays is turned back on after both were turned off
+    assert_eq!(ansi, "\x1b[1;2ma\x1b[22;2mb\x1b[22;1mc\x1b[0m\n");
+}
+
+#[test]
+fn colors_and_underlines_are_written_apart_from_the_modifiers() {
+    let bold = style(Modifier::BOLD);
+    let ansi = export::to_ansi(
+        &row(&[
+            (Some(Color::Red), bold),
+            (Some(Color::Green), bold),
+            (Some(Color::Green), bold.with_underline(Underline::Curly)),
+            (
+                Some(Color::Green),
+                bold.with_underline(Underline::Curly)
+                    .with_underline_color(Color::Blue),
+            ),
+            (Some(Color::Green), bold),
+        ]),
+        false,
+    );
+
+    assert_eq!(
+        ansi,
+        "\x1b[1;31ma\x1b[32mb\x1b[4:3mc\x1b[58;5;4md\x1b[24;59me\x1b[0m\n"
+    );
+}
+
+#[test]
+fn backends_write_the_same_transitions() {
+    let buffer = row(&[
+        (Some(Color::Red), style(Modifier::BOLD | Modifier::ITALIC)),
+        (Some(Color::Red), style(Modifier::ITALIC)),
+        (None, style(Modifier::ITALIC)),
+    ]);
+    let mut backend = AnsiBackend::new(Vec::new(), (3, 1));
+    backend.draw(&buffer).expect("writing to memory");
+
+    let written = String::from_utf8(backend.into_writer()).expect("utf-8 output");
+    assert_eq!(written, "\x1b[1;1H\x1b[1;3;31ma\x1b[22mb\x1b[39mc\x1b[0m");
+    assert_eq!(
+        export::to_ansi(&buffer, false),
+        "\x1b[1;3;31ma\x1b[22mb\x1b[39mc\x1b[0m\n"
+    );
+}
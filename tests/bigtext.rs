@@ -0,0 +1,64 @@
+use tixel::backend::{Backend, TestBackend};
+use tixel::bigtext::{BigFont, BigText, FigFont};
+use tixel::buffer::Buffer;
+use tixel::layout::Rect;
+
+// Two rows per glyph: the character over a bar, with `$` as the hard blank. Every required
+// glyph is defined, then one code-tagged glyph for ☺
+fn font() -> String {
+    let mut source = String::from("flf2a$ 2 1 4 0 2\ncomment\nanother comment\n");
+    for code in (32..127).chain([196, 214, 220, 228, 246, 252, 223]) {
+        let symbol = char::from_u32(code).unwrap();
+        match symbol {
+            ' ' => source.push_str("$@\n$@@\n"),
+            '@' => source.push_str("@#\n-#\n"), // Rows may end with any mark
+            _ => source.push_str(&format!("{}@\n-@@\n", symbol)),
+        }
+    }
+    source.push_str("0x263A  SMILING FACE\n:)@\n$-@@\n");
+    source
+}
+
+fn render(text: &str, font: FigFont, width: u16) -> TestBackend {
+    let area = Rect::new(0, 0, width, font.height() as u16);
+    let mut backend = TestBackend::new(area.width, area.height);
+    let mut buffer = Buffer::new(area);
+    BigText::new(text)
+        .font(BigFont::Figlet(font))
+        .render(&mut buffer, area);
+    backend.draw(&buffer).unwrap();
+    backend
+}
+
+#[test]
+fn parse_reads_the_required_and_code_tagged_glyphs() {
+    let font = FigFont::parse(&font()).unwrap();
+    assert_eq!(font.height(), 2);
+
+    render("Hi@☺Ä", font, 8).assert_lines(&["Hi@:)Ä  ", "--- --  "]);
+}
+
+#[test]
+fn parse_stops_at_the_glyphs_present() {
+    let source = "flf2a$ 1 1 2 0 0\n$@\n!@\n";
+    let font = FigFont::parse(source).unwrap();
+
+    render("!!", font, 3).assert_lines(&["!! "]);
+}
+
+#[test]
+fn parse_rejects_malformed_fonts() {
+    let error = |source: &str| FigFont::parse(source).unwrap_err().to_string();
+
+    assert_eq!(error(""), "empty FIGlet font");
+    assert_eq!(error("flf2 $ 2 1 4 0 0\n"), "missing the flf2a signature");
+    assert_eq!(error("flf2a$ 2 1\n"), "malformed FIGlet header");
+    assert_eq!(
+        error("flf2a$ 0 1 4 0 0\n"),
+        "FIGlet font with a height of 0"
+    );
+    assert_eq!(
+        error("flf2a$ 2 1 4 0 1\ncomment\n"),
+        "FIGlet font without glyphs"
+    );
+}
@@ -0,0 +1,172 @@
+use std::path::PathBuf;
+
+use tixel::backend::{Backend, TestBackend};
+use tixel::border::{Border, BorderType};
+use tixel::buffer::{Buffer, Cell};
+use tixel::layout::Rect;
+use tixel::symbols::BorderSet;
+use tixel::Color;
+
+const BORDER_TYPES: [(&str, BorderType); 12] = [
+    ("solid", BorderType::Solid),
+    ("dotted", BorderType::Dotted),
+    ("dashed", BorderType::Dashed),
+    ("double", BorderType::Double),
+    ("rounded", BorderType::Rounded),
+    ("thick", BorderType::Thick),
+    ("ascii", BorderType::Ascii),
+    ("block", BorderType::Block),
+    ("quadrant_inside", BorderType::QuadrantInside),
+    ("quadrant_outside", BorderType::QuadrantOutside),
+    ("none", BorderType::None),
+    (
+        "custom",
+        BorderType::Custom(BorderSet::new(['~', '~', '!', '!'], ['1', '2', '3', '4'])),
+    ),
+];
+const WIDTHS: [usize; 3] = [1, 2, 3];
+const PADDINGS: [usize; 3] = [0, 1, 2];
+
+// Size of the area given to each border, with one blank cell between neighbours
+const BOX_WIDTH: u16 = 16;
+const BOX_HEIGHT: u16 = 12;
+
+fn snapshot(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(name)
+}
+
+fn border(border_type: BorderType, width: usize, padding: usize) -> Border {
+    Border::new()
+        .border_type(border_type)
+        .width(width)
+        .padding(padding)
+        .ascii_fallback(false)
+        .build()
+        .expect("valid border")
+}
+
+// Every width across and every padding down, the content area filled with dots so the
+// padding shows as the blank ring around it
+fn render_grid(border_type: BorderType) -> TestBackend {
+    let columns = WIDTHS.len() as u16;
+    let rows = PADDINGS.len() as u16;
+    let area = Rect::new(
+        0,
+        0,
+        columns * (BOX_WIDTH + 1) - 1,
+        rows * (BOX_HEIGHT + 1) - 1,
+    );
+    let mut backend = TestBackend::new(area.width, area.height);
+    let mut buffer = Buffer::new(area);
+
+    for (row, padding) in PADDINGS.into_iter().enumerate() {
+        for (column, width) in WIDTHS.into_iter().enumerate() {
+            let area = Rect::new(
+                column as u16 * (BOX_WIDTH + 1),
+                row as u16 * (BOX_HEIGHT + 1),
+                BOX_WIDTH,
+                BOX_HEIGHT,
+            );
+            let border = border(border_type, width, padding);
+            border.render(&mut buffer, area).expect("border renders");
+
+            let inner = border.inner_rect(area);
+            for y_axis in inner.y..inner.y + inner.height {
+                for x_axis in inner.x..inner.x + inner.width {
+                    buffer.set_char(x_axis, y_axis, '.', Color::Reset);
+                }
+            }
+        }
+    }
+    backend.draw(&buffer).expect("test backend draws");
+    backend
+}
+
+#[test]
+fn border_types_widths_and_paddings_match_snapshots() {
+    for (name, border_type) in BORDER_TYPES {
+        render_grid(border_type).assert_snapshot(snapshot(&format!("border_{}.txt", name)));
+    }
+}
+
+#[test]
+fn inner_rect_leaves_width_and_padding_on_each_side() {
+    let area = Rect::new(0, 0, BOX_WIDTH, BOX_HEIGHT);
+    for (_, border_type) in BORDER_TYPES {
+        for width in WIDTHS {
+            for padding in PADDINGS {
+                let inset = (width + padding) as u16;
+                let expected =
+                    Rect::new(inset, inset, BOX_WIDTH - 2 * inset, BOX_HEIGHT - 2 * inset);
+                let border = border(border_type, width, padding);
+                assert_eq!(border.inner_rect(area), expected);
+                assert_eq!(border.outer_rect(expected), area);
+            }
+        }
+    }
+}
+
+#[test]
+fn margin_moves_the_border_inwards() {
+    let border = Border::new()
+        .width(1)
+        .margin(2)
+        .ascii_fallback(false)
+        .build()
+        .expect("valid border");
+    let area = Rect::new(0, 0, 10, 6);
+    let mut backend = TestBackend::new(area.width, area.height);
+    let mut buffer = Buffer::new(area);
+    border.render(&mut buffer, area).expect("border renders");
+    backend.draw(&buffer).expect("test backend draws");
+
+    backend.assert_lines(&[
+        "          ",
+        "          ",
+        "  ┌────┐  ",
+        "  └────┘  ",
+        "          ",
+        "          ",
+    ]);
+    assert_eq!(border.inner_rect(area), Rect::new(3, 3, 4, 0));
+}
+
+#[test]
+fn outer_rect_keeps_the_far_edges_when_clipped_at_the_origin() {
+    let border = border(BorderType::Solid, 2, 1);
+    let outer = border.outer_rect(Rect::new(1, 1, 4, 4));
+    assert_eq!(outer, Rect::new(0, 0, 8, 8));
+}
+
+#[test]
+fn layers_without_a_background_keep_the_one_below() {
+    let area = Rect::new(0, 0, 6, 6);
+    let render = |border: Border| {
+        let mut buffer = Buffer::new(area);
+        let mut blank = Cell::default();
+        blank.bg = Some(Color::Blue);
+        buffer.fill(area, &blank);
+        border.render(&mut buffer, area).expect("border renders");
+
+        let mut backend = TestBackend::new(area.width, area.height);
+        backend.draw(&buffer).expect("test backend draws");
+        backend
+    };
+
+    let backend = render(border(BorderType::Solid, 2, 0));
+    backend.assert_bg(0, 0, Some(Color::Blue));
+    backend.assert_bg(1, 1, Some(Color::Blue));
+
+    let backend = render(
+        Border::new()
+            .width(2)
+            .layer_backgrounds(vec![Color::Red, Color::Reset])
+            .ascii_fallback(false)
+            .build()
+            .expect("valid border"),
+    );
+    backend.assert_bg(0, 0, Some(Color::Red));
+    backend.assert_bg(1, 1, Some(Color::Reset));
+}
@@ -0,0 +1,154 @@
+use tixel::border::{Border, BorderAnimation, BorderBuilder, BorderError, Shadow, ShadowFill};
+use tixel::font::{FontStyle, Modifier};
+use tixel::gradient::{ColorSpace, Gradient};
+use tixel::{Color, HexColor};
+
+fn error(builder: BorderBuilder) -> BorderError {
+    match builder.build() {
+        Ok(_) => panic!("border built"),
+        Err(error) => error,
+    }
+}
+
+fn error_key(builder: BorderBuilder) -> &'static str {
+    error(builder).key()
+}
+
+#[test]
+fn zero_width_is_rejected() {
+    let error = error(Border::new().width(0));
+    assert_eq!(error, BorderError::ZeroWidth);
+    assert_eq!(error.key(), "width");
+}
+
+#[test]
+fn more_colors_than_layers_are_rejected() {
+    let error =
+        error(
+            Border::new()
+                .width(2)
+                .with_colors(vec![Color::Red, Color::Green, Color::Blue]),
+        );
+    assert_eq!(
+        error,
+        BorderError::TooManyColors {
+            colors: 3,
+            width: 2
+        }
+    );
+    assert_eq!(error.key(), "border_colors");
+    assert_eq!(
+        error.to_string(),
+        "3 colors provided for a border of width 2"
+    );
+}
+
+#[test]
+fn character_lists_must_match_the_width() {
+    let error = error(Border::new().width(2).vertical_border_char(vec!['|']));
+    assert_eq!(
+        error,
+        BorderError::CharCountMismatch {
+            key: "decoration_lines.vertical_char",
+            chars: 1,
+            width: 2
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "1 characters provided for a border of width 2"
+    );
+
+    // A single border character is repeated for every layer instead
+    assert!(Border::new().width(3).border_char('#').build().is_ok());
+}
+
+#[test]
+fn layer_lists_must_match_the_width() {
+    let error = error(
+        Border::new()
+            .width(1)
+            .horizontal_pattern(vec!["-=".into(), "=-".into()]),
+    );
+    assert_eq!(
+        error,
+        BorderError::LayerCountMismatch {
+            key: "decoration_lines.horizontal_pattern",
+            count: 2,
+            width: 1
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "2 layers provided for a border of width 1"
+    );
+
+    let styles = vec![FontStyle::from(Modifier::BOLD); 3];
+    assert_eq!(
+        error_key(Border::new().width(2).layer_styles(styles)),
+        "decoration_lines.font_style"
+    );
+    assert_eq!(
+        error_key(Border::new().width(2).layer_backgrounds(vec![Color::Black])),
+        "decoration_lines.background"
+    );
+}
+
+#[test]
+fn empty_patterns_are_rejected() {
+    let error = error(
+        Border::new()
+            .width(2)
+            .vertical_pattern(vec!["|".into(), String::new()]),
+    );
+    assert_eq!(
+        error,
+        BorderError::EmptyPattern {
+            key: "decoration_lines.vertical_pattern"
+        }
+    );
+}
+
+#[test]
+fn empty_gradients_are_rejected() {
+    let empty = || Gradient::new(Vec::new(), ColorSpace::Rgb);
+
+    assert_eq!(
+        error(Border::new().gradient(empty())),
+        BorderError::EmptyGradient
+    );
+    assert_eq!(
+        error(Border::new().layer_gradient(empty())),
+        BorderError::EmptyGradient
+    );
+    assert_eq!(BorderError::EmptyGradient.key(), "coloring");
+    assert!(Border::new()
+        .gradient(Gradient::new(vec![HexColor::BLACK], ColorSpace::Rgb))
+        .build()
+        .is_ok());
+}
+
+#[test]
+fn pulses_need_a_period() {
+    let error = error(Border::new().animation(BorderAnimation::Pulse { period: 0 }));
+    assert_eq!(error, BorderError::ZeroPulsePeriod);
+    assert_eq!(error.key(), "animation.period");
+}
+
+#[test]
+fn shadow_brightness_stays_between_0_and_1() {
+    let shadow = |brightness| Shadow {
+        offset_x: 1,
+        offset_y: 1,
+        fill: ShadowFill::Darken(brightness),
+    };
+
+    for brightness in [-0.1, 1.5, f64::NAN] {
+        let error = error(Border::new().shadow(shadow(brightness)));
+        assert_eq!(error, BorderError::InvalidShadowBrightness);
+        assert_eq!(error.key(), "shadow.fill");
+    }
+    for brightness in [0.0, 0.5, 1.0] {
+        assert!(Border::new().shadow(shadow(brightness)).build().is_ok());
+    }
+}
@@ -0,0 +1,100 @@
+use tixel::config::{Config, ConfigError, ConfigFormat};
+use tixel::layout::Rect;
+use tixel::theme::Appearance;
+
+// Key and message of the error a TOML configuration is rejected with
+fn error(source: &str) -> (String, String) {
+    match Config::parse(source, ConfigFormat::Toml) {
+        Ok(_) => panic!("configuration accepted:\n{}", source),
+        Err(ConfigError::Parse { key, message } | ConfigError::Invalid { key, message }) => {
+            (key, message)
+        }
+        Err(error) => panic!("unexpected error: {}", error),
+    }
+}
+
+#[test]
+fn unknown_keys_are_rejected_with_their_name() {
+    let (key, message) = error("[border]\nbordr = 1\n");
+    assert_eq!(key, "border.bordr");
+    assert!(message.contains("unknown field `bordr`"), "{}", message);
+
+    let (key, message) = error("[theme]\nbackground = \"#000000\"\n");
+    assert_eq!(key, "theme.background");
+    assert!(
+        message.contains("unknown field `background`"),
+        "{}",
+        message
+    );
+
+    let (key, message) = error("[layout]\n[[layout.children]]\nconstrant = \"fill\"\n");
+    assert_eq!(key, "layout.children[0].constrant");
+    assert!(message.contains("unknown field `constrant`"), "{}", message);
+
+    assert_eq!(error("colour = \"red\"\n").0, "colour");
+}
+
+#[test]
+fn malformed_values_name_their_key() {
+    assert_eq!(
+        error("[theme]\nbackground_color = \"#12345\"\n").0,
+        "theme.background_color"
+    );
+    assert_eq!(error("[border]\nwidth = -1\n").0, "border.width");
+    assert_eq!(
+        error("[border]\nborder_colors = [\"#FFFFFF\", \"blue\"]\n").0,
+        "border.border_colors"
+    );
+}
+
+#[test]
+fn invalid_values_name_their_key() {
+    assert_eq!(
+        error("[border]\nwidth = 1\nborder_colors = [\"#FFFFFF\", \"#000000\"]\n").0,
+        "border.border_colors"
+    );
+    assert_eq!(error("[border]\nwidth = 0\n").0, "border.width");
+    assert_eq!(
+        error("[layout]\n[[layout.children]]\nconstraint = { percentage = 60 }\n[[layout.children]]\nconstraint = { percentage = 50 }\n").0,
+        "layout.children"
+    );
+    assert_eq!(
+        error("[layout]\n[[layout.children]]\n[layout.children.border]\nwidth = 0\n").0,
+        "layout.children[0].border.width"
+    );
+}
+
+#[test]
+fn every_format_reports_the_same_key() {
+    let json = Config::parse(r#"{"border": {"bordr": 1}}"#, ConfigFormat::Json);
+    let ron = Config::parse("(border: Some((bordr: 1)))", ConfigFormat::Ron);
+
+    for result in [json, ron] {
+        match result {
+            Err(ConfigError::Parse { key, message }) => {
+                assert_eq!(key, "border.bordr");
+                assert!(message.contains("bordr"), "{}", message);
+            }
+            _ => panic!("unknown key accepted"),
+        }
+    }
+}
+
+#[test]
+fn valid_configurations_parse() {
+    let config = Config::parse(
+        "appearance = \"light\"\n\
+         [theme]\nbackground_color = \"#FFFFFF\"\nforeground_color = \"#000000\"\n\
+         [border]\nwidth = 2\nborder_colors = [\"#FF0000\", \"#00FF00\"]\n\
+         [layout]\ndirection = \"horizontal\"\n",
+        ConfigFormat::Toml,
+    )
+    .unwrap();
+
+    assert_eq!(config.appearance, Some(Appearance::Light));
+    let border = config.border.expect("border");
+    assert_eq!(
+        border.inner_rect(Rect::new(0, 0, 10, 10)),
+        Rect::new(2, 2, 6, 6)
+    );
+}
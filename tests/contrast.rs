@@ -0,0 +1,114 @@
+use tixel::buffer::Buffer;
+use tixel::contrast::{self, ContrastAction, ContrastCheck, ContrastLevel};
+use tixel::layout::Rect;
+use tixel::{Color, HexColor};
+
+// Gray text passing AA but not AAA over black
+const GRAY: HexColor = HexColor::from_rgb(0x77, 0x77, 0x77);
+
+fn cell(buffer: &mut Buffer, x_axis: u16, symbol: &str, fg: Option<Color>, bg: Option<Color>) {
+    let cell = buffer.get_mut(x_axis, 0).expect("cell inside the buffer");
+    cell.symbol = String::from(symbol);
+    cell.fg = fg;
+    cell.bg = bg;
+}
+
+// Gray over black, a blank, the default colors, and the default text color over white
+fn frame() -> Buffer {
+    let mut buffer = Buffer::new(Rect::new(0, 0, 4, 1));
+    cell(&mut buffer, 0, "a", Some(GRAY.into()), Some(Color::Black));
+    cell(&mut buffer, 1, " ", Some(Color::Black), Some(Color::Black));
+    cell(&mut buffer, 2, "b", None, None);
+    cell(&mut buffer, 3, "c", None, Some(HexColor::WHITE.into()));
+    buffer
+}
+
+fn colors(buffer: &Buffer, x_axis: u16) -> (Option<Color>, Option<Color>) {
+    let cell = buffer.get(x_axis, 0).expect("cell inside the buffer");
+    (cell.fg, cell.bg)
+}
+
+#[test]
+fn levels_hold_text_to_their_ratio() {
+    let ratio = GRAY.contrast_ratio(&HexColor::BLACK);
+    assert!(ratio > 4.5 && ratio < 7.0, "{}", ratio);
+
+    let check = |level| ContrastCheck::new(level, ContrastAction::Highlight).apply(&mut frame());
+    assert_eq!(check(ContrastLevel::Aa), 1);
+    assert_eq!(check(ContrastLevel::Aaa), 2);
+}
+
+#[test]
+fn cells_without_colors_are_checked_in_the_terminal_defaults() {
+    let mut buffer = frame();
+    ContrastCheck::new(ContrastLevel::Aa, ContrastAction::Highlight).apply(&mut buffer);
+
+    // The xterm palette shows the default text in light gray over black
+    assert_eq!(colors(&buffer, 2), (None, None));
+    assert_ne!(colors(&buffer, 3).1, Some(HexColor::WHITE.into()));
+}
+
+#[test]
+fn failing_cells_are_highlighted() {
+    let mut buffer = frame();
+    ContrastCheck::new(ContrastLevel::Aaa, ContrastAction::Highlight).apply(&mut buffer);
+
+    let highlight = (
+        Some(HexColor::from_rgb(0, 0, 0).into()),
+        Some(HexColor::from_rgb(255, 0, 255).into()),
+    );
+    assert_eq!(colors(&buffer, 0), highlight);
+    assert_eq!(colors(&buffer, 1), (Some(Color::Black), Some(Color::Black)));
+    assert_eq!(colors(&buffer, 3), highlight);
+}
+
+#[test]
+fn failing_text_is_adjusted_and_the_background_kept() {
+    let mut buffer = frame();
+    assert_eq!(
+        ContrastCheck::new(ContrastLevel::Aaa, ContrastAction::Adjust).apply(&mut buffer),
+        2
+    );
+
+    let (fg, bg) = colors(&buffer, 0);
+    assert_eq!(bg, Some(Color::Black));
+    let fg = fg.and_then(|fg| fg.to_hex()).expect("adjusted text color");
+    assert!(fg.contrast_ratio(&HexColor::BLACK) >= 7.0);
+
+    let (fg, bg) = colors(&buffer, 3);
+    assert_eq!(bg, Some(HexColor::WHITE.into()));
+    let fg = fg.and_then(|fg| fg.to_hex()).expect("adjusted text color");
+    assert!(fg.contrast_ratio(&HexColor::WHITE) >= 7.0);
+}
+
+#[test]
+fn readable_colors_move_just_enough() {
+    // Colors with enough contrast are kept
+    assert_eq!(contrast::readable(&GRAY, &HexColor::BLACK, 4.5), GRAY);
+
+    // Lighter over dark backgrounds, darker over light ones, and barely above the ratio
+    let lighter = contrast::readable(&GRAY, &HexColor::BLACK, 7.0);
+    assert!(lighter.luminance() > GRAY.luminance());
+    let ratio = lighter.contrast_ratio(&HexColor::BLACK);
+    assert!((7.0..7.1).contains(&ratio), "{}", ratio);
+
+    let darker = contrast::readable(&GRAY, &HexColor::WHITE, 7.0);
+    assert!(darker.luminance() < GRAY.luminance());
+    let ratio = darker.contrast_ratio(&HexColor::WHITE);
+    assert!((7.0..7.1).contains(&ratio), "{}", ratio);
+}
+
+#[test]
+fn unreachable_ratios_give_the_strongest_color() {
+    // Neither white nor black reach 7.0 over this gray, white comes closer
+    let gray = HexColor::from_rgb(0x70, 0x70, 0x70);
+    assert!(HexColor::WHITE.contrast_ratio(&gray) < 7.0);
+    assert!(HexColor::WHITE.contrast_ratio(&gray) > HexColor::BLACK.contrast_ratio(&gray));
+
+    let fg = HexColor::from_rgb(0x80, 0x80, 0x80);
+    assert_eq!(contrast::readable(&fg, &gray, 7.0), HexColor::WHITE);
+    assert_eq!(
+        contrast::readable(&fg, &HexColor::WHITE, 21.0),
+        HexColor::BLACK
+    );
+}
@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::Path;
+
+use tixel::backend::{Backend, TestBackend};
+use tixel::buffer::Buffer;
+use tixel::export::{self, ExportFormat};
+use tixel::font::Modifier;
+use tixel::layout::Rect;
+use tixel::theme::Theme;
+use tixel::Color;
+
+fn frame() -> Buffer {
+    let mut buffer = Buffer::new(Rect::new(0, 0, 4, 2));
+    buffer.set_char(0, 0, 'a', Color::Red);
+    buffer.set_char(1, 0, '漢', Color::Reset);
+    if let Some(cell) = buffer.get_mut(0, 1) {
+        cell.symbol = String::from("b");
+        cell.style = Modifier::BOLD.into();
+    }
+    buffer
+}
+
+#[test]
+fn formats_follow_the_file_extension() {
+    let format = |path: &str| ExportFormat::from_path(Path::new(path));
+
+    assert_eq!(format("frame.html"), Some(ExportFormat::Html));
+    assert_eq!(format("frame.htm"), Some(ExportFormat::Html));
+    assert_eq!(format("frame.svg"), Some(ExportFormat::Svg));
+    assert_eq!(
+        format("frame.ans"),
+        Some(ExportFormat::Ansi { links: false })
+    );
+    assert_eq!(
+        format("frame.ansi"),
+        Some(ExportFormat::Ansi { links: false })
+    );
+    assert_eq!(format("frame.txt"), Some(ExportFormat::Text));
+    assert_eq!(format("frame.png"), None);
+    assert_eq!(format("frame"), None);
+}
+
+#[test]
+fn text_exports_hold_the_symbols_alone() {
+    let text = export::export(&frame(), &Theme::default(), ExportFormat::Text);
+    assert_eq!(text, "a漢 \nb   \n");
+}
+
+#[test]
+fn exports_match_the_snapshots_of_the_same_extension() {
+    let buffer = frame();
+    let mut backend = TestBackend::new(4, 2);
+    backend.draw(&buffer).unwrap();
+
+    let directory = std::env::temp_dir().join(format!("tixel-export-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    for name in ["frame.txt", "frame.ans", "frame.ansi"] {
+        let path = directory.join(name);
+        export::save(&buffer, &Theme::default(), &path).unwrap();
+        backend.assert_snapshot(&path);
+    }
+    assert!(fs::read_to_string(directory.join("frame.ans"))
+        .unwrap()
+        .contains("\x1b[31ma"));
+    fs::remove_dir_all(directory).unwrap();
+}
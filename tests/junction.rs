@@ -0,0 +1,130 @@
+use tixel::backend::{Backend, TestBackend};
+use tixel::border::{Border, BorderType};
+use tixel::buffer::Buffer;
+use tixel::layout::{Constraint, Direction, LayoutNode, Rect};
+use tixel::Color;
+
+// Symbol of a single cell after drawing `glyphs` into it one after the other
+fn merged(glyphs: &[char]) -> String {
+    let mut buffer = Buffer::new(Rect::new(0, 0, 1, 1));
+    for glyph in glyphs {
+        buffer.set_line(0, 0, *glyph, Color::Reset);
+    }
+    buffer.get(0, 0).unwrap().symbol.clone()
+}
+
+fn draw(buffer: &Buffer) -> TestBackend {
+    let area = buffer.area();
+    let mut backend = TestBackend::new(area.width, area.height);
+    backend.draw(buffer).unwrap();
+    backend
+}
+
+fn border(border_type: BorderType) -> Border {
+    Border::new()
+        .border_type(border_type)
+        .ascii_fallback(false)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn single_lines_merge_into_junctions() {
+    assert_eq!(merged(&['─', '│']), "┼");
+    assert_eq!(merged(&['─', '┌', '┐']), "┬");
+    assert_eq!(merged(&['─', '└']), "┴");
+    assert_eq!(merged(&['│', '┌']), "├");
+    assert_eq!(merged(&['│', '┘']), "┤");
+    assert_eq!(merged(&['┬', '┴']), "┼");
+    assert_eq!(merged(&['├', '┤']), "┼");
+}
+
+#[test]
+fn mixed_weights_merge_into_mixed_junctions() {
+    assert_eq!(merged(&['═', '│']), "╪");
+    assert_eq!(merged(&['║', '─']), "╫");
+    assert_eq!(merged(&['═', '║']), "╬");
+    assert_eq!(merged(&['┌', '║']), "╟");
+    assert_eq!(merged(&['┐', '═']), "╤");
+    assert_eq!(merged(&['╔', '╝']), "╬");
+}
+
+#[test]
+fn the_glyph_drawn_last_wins_where_both_draw() {
+    assert_eq!(merged(&['║', '┌']), "├");
+    assert_eq!(merged(&['│', '╔']), "╠");
+    assert_eq!(merged(&['┃', '═']), "╪");
+    assert_eq!(merged(&['━', '│']), "┿");
+}
+
+#[test]
+fn glyphs_adding_nothing_are_kept_as_drawn() {
+    assert_eq!(merged(&['─', '╌']), "╌");
+    assert_eq!(merged(&['┌', '╭']), "╭");
+    assert_eq!(merged(&['│', 'x']), "x");
+    assert_eq!(merged(&['x', '│']), "│");
+}
+
+#[test]
+fn adjacent_borders_share_their_junctions() {
+    let mut buffer = Buffer::new(Rect::new(0, 0, 9, 5));
+    let solid = border(BorderType::Solid);
+    solid.render(&mut buffer, Rect::new(0, 0, 5, 3)).unwrap();
+    solid.render(&mut buffer, Rect::new(4, 0, 5, 3)).unwrap();
+    border(BorderType::Double)
+        .render(&mut buffer, Rect::new(0, 2, 9, 3))
+        .unwrap();
+
+    draw(&buffer).assert_lines(&[
+        "┌───┬───┐",
+        "│   │   │",
+        "╠═══╧═══╣",
+        "║       ║",
+        "╚═══════╝",
+    ]);
+}
+
+#[test]
+fn collapsed_layouts_share_the_borders_of_their_children() {
+    let cell = || LayoutNode {
+        border: Some(border(BorderType::Solid)),
+        ..LayoutNode::default()
+    };
+    let row = || LayoutNode {
+        direction: Direction::Horizontal,
+        collapse_borders: true,
+        children: vec![
+            LayoutNode {
+                constraint: Constraint::Length(4),
+                ..cell()
+            },
+            cell(),
+        ],
+        ..LayoutNode::default()
+    };
+    let layout = LayoutNode {
+        direction: Direction::Vertical,
+        border: Some(border(BorderType::Solid)),
+        collapse_borders: true,
+        children: vec![
+            LayoutNode {
+                constraint: Constraint::Length(2),
+                ..row()
+            },
+            row(),
+        ],
+        ..LayoutNode::default()
+    };
+
+    let area = Rect::new(0, 0, 9, 5);
+    let mut buffer = Buffer::new(area);
+    layout.render(&mut buffer, area).unwrap();
+
+    draw(&buffer).assert_lines(&[
+        "┌───┬───┐",
+        "│   │   │",
+        "├───┼───┤",
+        "│   │   │",
+        "└───┴───┘",
+    ]);
+}
@@ -0,0 +1,127 @@
+use tixel::backend::{Backend, TestBackend};
+use tixel::buffer::Buffer;
+use tixel::font::Modifier;
+use tixel::layout::Rect;
+use tixel::record::{CastEvent, CastEventKind, Recording, RecordingBackend, Replay};
+use tixel::Color;
+
+const HEADER: &str = r#"{"version": 2, "width": 6, "height": 2, "timestamp": 0}"#;
+
+fn cast(events: &[&str]) -> String {
+    let mut lines = vec![HEADER];
+    lines.extend_from_slice(events);
+    lines.join("\n")
+}
+
+#[test]
+fn parse_reads_the_header_and_every_kind_of_event() {
+    let source = cast(&[
+        r#"[0.5, "o", "hi"]"#,
+        "",
+        r#"[1.0, "i", "q"]"#,
+        r#"[1.5, "m", "chapter"]"#,
+        r#"[2.0, "r", "8x3"]"#,
+    ]);
+    let recording = Recording::parse(&source).unwrap();
+
+    assert_eq!((recording.width, recording.height), (6, 2));
+    assert_eq!(
+        recording.events,
+        vec![
+            CastEvent {
+                time: 0.5,
+                kind: CastEventKind::Output("hi".into())
+            },
+            CastEvent {
+                time: 1.0,
+                kind: CastEventKind::Input("q".into())
+            },
+            CastEvent {
+                time: 1.5,
+                kind: CastEventKind::Marker("chapter".into())
+            },
+            CastEvent {
+                time: 2.0,
+                kind: CastEventKind::Resize(8, 3)
+            },
+        ]
+    );
+}
+
+#[test]
+fn parse_skips_unknown_event_types() {
+    let recording =
+        Recording::parse(&cast(&[r#"[0.1, "x", "later"]"#, r#"[0.2, "o", "a"]"#])).unwrap();
+
+    assert_eq!(
+        recording.events,
+        vec![CastEvent {
+            time: 0.2,
+            kind: CastEventKind::Output("a".into())
+        }]
+    );
+}
+
+#[test]
+fn parse_rejects_malformed_recordings() {
+    let error = |source: &str| Recording::parse(source).unwrap_err().to_string();
+
+    assert_eq!(error(""), "line 1: missing header");
+    assert_eq!(
+        error(r#"{"version": 1, "width": 6, "height": 2}"#),
+        "line 1: only asciicast version 2 is supported"
+    );
+    assert_eq!(
+        error(r#"{"version": 2, "width": 6}"#),
+        "line 1: missing height"
+    );
+    assert_eq!(
+        error(&cast(&[r#"[0.1, "o", "a"]"#, r#"[0.2, "r", "80"]"#])),
+        "line 3: malformed resize"
+    );
+    assert!(Recording::parse(&cast(&["[0.1, \"o\""])).is_err());
+}
+
+#[test]
+fn replay_plays_output_and_resizes_to_a_blank_screen() {
+    let source = cast(&[
+        r#"[0.1, "o", "\u001b[1;1H\u001b[31mhi"]"#,
+        r#"[0.2, "r", "8x3"]"#,
+        r#"[0.3, "o", "\u001b[3;7H\u001b[0m\u001b[1mok"]"#,
+    ]);
+    let recording = Recording::parse(&source).unwrap();
+    let mut replay = Replay::new(&recording);
+
+    replay.seek(0.1);
+    replay.backend().assert_lines(&["hi    ", "      "]);
+    replay.backend().assert_fg(0, 0, Some(Color::Red));
+
+    replay.finish();
+    assert_eq!(replay.time(), 0.3);
+    replay
+        .backend()
+        .assert_lines(&["        ", "        ", "      ok"]);
+    replay.backend().assert_style(6, 2, Modifier::BOLD.into());
+}
+
+#[test]
+fn recordings_replay_what_was_drawn() {
+    let area = Rect::new(0, 0, 5, 2);
+    let mut buffer = Buffer::new(area);
+    buffer.set_char(0, 0, 'a', Color::Green);
+    buffer.set_char(1, 0, 'b', Color::Green);
+    buffer.set_char(1, 1, '漢', Color::Reset);
+    buffer.set_char(3, 1, 'x', Color::Reset);
+
+    let mut recorder = RecordingBackend::new(TestBackend::new(5, 2), Vec::new()).unwrap();
+    recorder.draw(&buffer).unwrap();
+    recorder.flush().unwrap();
+    let (inner, cast) = recorder.finish().unwrap();
+
+    let recording = Recording::parse(&String::from_utf8(cast).unwrap()).unwrap();
+    let mut replay = Replay::new(&recording);
+    replay.finish();
+
+    assert_eq!(replay.backend().to_text(), inner.to_text());
+    replay.backend().assert_fg(1, 0, Some(Color::Green));
+}
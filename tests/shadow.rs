@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+use tixel::backend::{Backend, TestBackend};
+use tixel::border::{Bevel, Border, Shadow, ShadowFill};
+use tixel::buffer::{Buffer, Cell};
+use tixel::layout::Rect;
+use tixel::{Color, HexColor};
+
+fn snapshot(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(name)
+}
+
+// A 6x4 border with its shadow, over a 9x6 area painted with `background`
+fn render(border: Border, background: Option<Color>) -> TestBackend {
+    let area = Rect::new(0, 0, 9, 6);
+    let mut buffer = Buffer::new(area);
+    let mut blank = Cell::default();
+    blank.bg = background;
+    buffer.fill(area, &blank);
+    border
+        .render(&mut buffer, Rect::new(1, 1, 6, 4))
+        .expect("border renders");
+
+    let mut backend = TestBackend::new(area.width, area.height);
+    backend.draw(&buffer).expect("test backend draws");
+    backend
+}
+
+fn shadowed(fill: ShadowFill) -> Border {
+    Border::new()
+        .ascii_fallback(false)
+        .with_color(Color::White)
+        .shadow(Shadow {
+            offset_x: 2,
+            offset_y: 1,
+            fill,
+        })
+        .build()
+        .expect("valid border")
+}
+
+#[test]
+fn glyph_shadows_overwrite_the_cells_below() {
+    let backend = render(
+        shadowed(ShadowFill::Glyph {
+            symbol: '▒',
+            color: Color::BrightBlack,
+        }),
+        None,
+    );
+
+    backend.assert_lines(&[
+        "         ",
+        " ┌────┐  ",
+        " │    │▒▒",
+        " │    │▒▒",
+        " └────┘▒▒",
+        "   ▒▒▒▒▒▒",
+    ]);
+    backend.assert_fg(8, 3, Some(Color::BrightBlack));
+    backend.assert_snapshot(snapshot("shadow_glyph.ans"));
+}
+
+#[test]
+fn darkening_shadows_only_change_colors() {
+    let backend = render(shadowed(ShadowFill::Darken(0.5)), Some(Color::Blue));
+
+    backend.assert_lines(&[
+        "         ",
+        " ┌────┐  ",
+        " │    │  ",
+        " │    │  ",
+        " └────┘  ",
+        "         ",
+    ]);
+    backend.assert_bg(8, 5, Some(HexColor::from_rgb(0, 0, 119).into()));
+    backend.assert_bg(0, 5, Some(Color::Blue));
+    backend.assert_snapshot(snapshot("shadow_darken.ans"));
+}
+
+#[test]
+fn full_brightness_shadows_leave_blank_cells_blank() {
+    let backend = render(shadowed(ShadowFill::Darken(1.0)), Some(Color::Black));
+
+    backend.assert_text(3, 5, "      ");
+    backend.assert_text(7, 2, "  ");
+}
+
+#[test]
+fn darkening_shadows_shade_black_backgrounds() {
+    let backend = render(shadowed(ShadowFill::Darken(0.5)), Some(Color::Black));
+
+    backend.assert_text(3, 5, "░░░░░░");
+    backend.assert_text(7, 2, "░░");
+}
+
+#[test]
+fn bevels_light_and_shade_opposite_sides() {
+    for (name, bevel) in [("raised", Bevel::Raised), ("sunken", Bevel::Sunken)] {
+        let border = Border::new()
+            .ascii_fallback(false)
+            .with_color(HexColor::from_rgb(0x80, 0x80, 0x80))
+            .bevel(bevel)
+            .build()
+            .expect("valid border");
+
+        render(border, None).assert_snapshot(snapshot(&format!("bevel_{}.ans", name)));
+    }
+}
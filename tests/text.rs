@@ -0,0 +1,292 @@
+use tixel::backend::{Backend, TestBackend};
+use tixel::buffer::Buffer;
+use tixel::font::{FontStyle, Modifier, TextAlignment, TextDirection, Underline};
+use tixel::layout::Rect;
+use tixel::text::{Line, MarkupError, Span, Text};
+use tixel::{Color, HexColor};
+
+fn spans(text: &Text) -> Vec<Vec<Span>> {
+    text.lines.iter().map(|line| line.spans.clone()).collect()
+}
+
+#[test]
+fn markup_applies_styles_and_colors() {
+    let text = Text::from_markup("[bold red]error[/]: [italic on blue]disk[/] full").unwrap();
+
+    assert_eq!(
+        spans(&text),
+        vec![vec![
+            Span::new("error").fg(Color::Red).style(Modifier::BOLD),
+            Span::new(": "),
+            Span::new("disk").bg(Color::Blue).style(Modifier::ITALIC),
+            Span::new(" full"),
+        ]]
+    );
+}
+
+#[test]
+fn markup_reads_hex_and_indexed_colors() {
+    let text = Text::from_markup("[#ff8000 on 236]warm[/]").unwrap();
+
+    assert_eq!(
+        spans(&text),
+        vec![vec![Span::new("warm")
+            .fg(HexColor::from_rgb(255, 128, 0))
+            .bg(Color::Indexed(236))]]
+    );
+}
+
+#[test]
+fn markup_reads_underline_shapes_and_links() {
+    let text =
+        Text::from_markup("[underline:curly]typo[/] [underline link=https://example.com]docs[/]")
+            .unwrap();
+
+    assert_eq!(
+        spans(&text),
+        vec![vec![
+            Span::new("typo").style(Underline::Curly),
+            Span::new(" "),
+            Span::new("docs")
+                .style(Underline::Single)
+                .link("https://example.com"),
+        ]]
+    );
+}
+
+#[test]
+fn markup_nests_tags_and_closes_them_by_name() {
+    let text = Text::from_markup("[bold]a[red]b[/red]c[/bold]d").unwrap();
+
+    assert_eq!(
+        spans(&text),
+        vec![vec![
+            Span::new("a").style(Modifier::BOLD),
+            Span::new("b").fg(Color::Red).style(Modifier::BOLD),
+            Span::new("c").style(Modifier::BOLD),
+            Span::new("d"),
+        ]]
+    );
+}
+
+#[test]
+fn markup_escapes_brackets_and_splits_lines() {
+    let text = Text::from_markup("[[x]\n[dim]y[/]").unwrap();
+
+    assert_eq!(
+        spans(&text),
+        vec![
+            vec![Span::new("[x]")],
+            vec![Span::new("y").style(Modifier::DIM)],
+        ]
+    );
+}
+
+#[test]
+fn markup_round_trips() {
+    let markup = "[bold red]error[/]: [[1] [underline:dashed on #102030]x[/]\n\
+                  [link=https://example.com]site[/]";
+    let text = Text::from_markup(markup).unwrap();
+
+    assert_eq!(Text::from_markup(&text.to_markup()).unwrap(), text);
+}
+
+#[test]
+fn markup_reports_errors_with_the_offset_of_the_tag() {
+    assert_eq!(
+        Text::from_markup("ok [bold"),
+        Err(MarkupError::Unterminated { position: 3 })
+    );
+    assert_eq!(
+        Text::from_markup("[bold shiny]x[/]"),
+        Err(MarkupError::UnknownStyle {
+            word: "shiny".into(),
+            position: 0
+        })
+    );
+    assert_eq!(
+        Text::from_markup("[underline:wavy]x[/]"),
+        Err(MarkupError::UnknownStyle {
+            word: "underline:wavy".into(),
+            position: 0
+        })
+    );
+    assert_eq!(
+        Text::from_markup("[bold]x[/red]"),
+        Err(MarkupError::UnexpectedClose {
+            tag: "/red".into(),
+            position: 7
+        })
+    );
+    assert_eq!(
+        Text::from_markup("x[/]"),
+        Err(MarkupError::UnexpectedClose {
+            tag: "/".into(),
+            position: 1
+        })
+    );
+}
+
+#[test]
+fn ansi_keeps_sgr_colors_and_styles() {
+    let text = Text::from_ansi("\x1b[1;31merror\x1b[0m: \x1b[38;2;1;2;3;48;5;17mrgb\x1b[m\n");
+
+    assert_eq!(
+        spans(&text),
+        vec![vec![
+            Span::new("error").fg(Color::Red).style(Modifier::BOLD),
+            Span::new(": "),
+            Span::new("rgb")
+                .fg(HexColor::from_rgb(1, 2, 3))
+                .bg(Color::Indexed(17)),
+        ]]
+    );
+}
+
+#[test]
+fn ansi_keeps_osc8_links() {
+    let text = Text::from_ansi(
+        "see \x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x1b\\ or \x1b]8;id=1;https://a.b\x07a\x1b]8;;\x07",
+    );
+
+    assert_eq!(
+        spans(&text),
+        vec![vec![
+            Span::new("see "),
+            Span::new("docs").link("https://example.com"),
+            Span::new(" or "),
+            Span::new("a").link("https://a.b"),
+        ]]
+    );
+}
+
+#[test]
+fn ansi_expands_tabs_and_drops_other_sequences() {
+    let text = Text::from_ansi("ab\tc\x1b[2K\x1b[10;4Hd\x07\re\n\tf");
+
+    assert_eq!(
+        spans(&text),
+        vec![vec![Span::new("ab      cde")], vec![Span::new("        f")],]
+    );
+}
+
+#[test]
+fn ansi_keeps_a_trailing_empty_line_only_when_it_is_the_only_one() {
+    assert_eq!(Text::from_ansi("").height(), 1);
+    assert_eq!(Text::from_ansi("a\n").height(), 1);
+    assert_eq!(Text::from_ansi("a\n\n").height(), 2);
+}
+
+// Style and colors of the single span `Text::from_ansi` makes of "x" after `sgr`
+fn pen(sgr: &str) -> Span {
+    let text = Text::from_ansi(&format!("{}x", sgr));
+    text.lines[0].spans[0].clone()
+}
+
+#[test]
+fn sgr_sets_and_clears_modifiers() {
+    assert_eq!(
+        pen("\x1b[1;3;9m").style,
+        FontStyle::from(Modifier::BOLD | Modifier::ITALIC | Modifier::STRIKETHROUGH)
+    );
+    assert_eq!(pen("\x1b[1;2;22m").style, FontStyle::default());
+    assert_eq!(
+        pen("\x1b[5;7;8;53m\x1b[25;27;28;55m").style,
+        FontStyle::default()
+    );
+    assert_eq!(pen("\x1b[1;31;42m\x1b[0m"), Span::new("x"));
+    assert_eq!(pen("\x1b[1;31m\x1b[m"), Span::new("x"));
+}
+
+#[test]
+fn sgr_selects_underline_shapes() {
+    let underline = |sgr: &str| pen(sgr).style.underline_style();
+
+    assert_eq!(underline("\x1b[4m"), Underline::Single);
+    assert_eq!(underline("\x1b[4:3m"), Underline::Curly);
+    assert_eq!(underline("\x1b[4:5m"), Underline::Dashed);
+    assert_eq!(underline("\x1b[21m"), Underline::Double);
+    assert_eq!(underline("\x1b[4;4:0m"), Underline::None);
+    assert_eq!(underline("\x1b[4;24m"), Underline::None);
+}
+
+#[test]
+fn sgr_selects_colors_in_every_form() {
+    assert_eq!(pen("\x1b[33;104m").fg, Some(Color::Yellow));
+    assert_eq!(pen("\x1b[33;104m").bg, Some(Color::BrightBlue));
+    assert_eq!(pen("\x1b[38;5;208m").fg, Some(Color::Indexed(208)));
+    assert_eq!(
+        pen("\x1b[48;2;10;20;30m").bg,
+        Some(HexColor::from_rgb(10, 20, 30).into())
+    );
+    assert_eq!(pen("\x1b[38:5:99m").fg, Some(Color::Indexed(99)));
+    assert_eq!(
+        pen("\x1b[38:2::1:2:3m").fg,
+        Some(HexColor::from_rgb(1, 2, 3).into())
+    );
+    assert_eq!(
+        pen("\x1b[38:2:4:5:6m").fg,
+        Some(HexColor::from_rgb(4, 5, 6).into())
+    );
+    assert_eq!(
+        pen("\x1b[38;2;300;0;0m").fg,
+        Some(HexColor::from_rgb(255, 0, 0).into())
+    );
+
+    // The parameters after an incomplete color are still applied
+    assert_eq!(
+        pen("\x1b[38;7;1m").style,
+        FontStyle::from(Modifier::REVERSE | Modifier::BOLD)
+    );
+    assert_eq!(pen("\x1b[31;42;39;49m"), Span::new("x"));
+}
+
+#[test]
+fn sgr_selects_the_underline_color() {
+    let color = |sgr: &str| pen(sgr).style.underline_color();
+
+    assert_eq!(color("\x1b[58;5;1m"), Some(Color::Indexed(1)));
+    assert_eq!(
+        color("\x1b[58:2::7:8:9m"),
+        Some(HexColor::from_rgb(7, 8, 9).into())
+    );
+    assert_eq!(color("\x1b[58;5;1;59m"), None);
+}
+
+#[test]
+fn link_hints_underline_links_and_follow_them_with_their_url() {
+    let text = Text::from(Line::new(vec![
+        Span::new("docs").link("https://example.com"),
+        Span::new("!"),
+    ]));
+
+    assert_eq!(
+        spans(&text.with_link_hints()),
+        vec![vec![
+            Span::new("docs")
+                .style(Underline::Single)
+                .link("https://example.com"),
+            Span::new(" (https://example.com)")
+                .style(Modifier::DIM)
+                .link("https://example.com"),
+            Span::new("!"),
+        ]]
+    );
+}
+
+#[test]
+fn right_to_left_lines_are_reordered_and_mirrored() {
+    let area = Rect::new(0, 0, 8, 1);
+    let mut backend = TestBackend::new(area.width, area.height);
+    let mut buffer = Buffer::new(area);
+    Text::from(Line::from("אב (ג)")).render(
+        &mut buffer,
+        area,
+        TextAlignment::Left,
+        TextDirection::Auto,
+    );
+    backend.draw(&buffer).unwrap();
+
+    // Right to left lines start from the right edge, so a left alignment is mirrored
+    backend.assert_lines(&["  (ג) בא"]);
+}
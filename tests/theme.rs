@@ -0,0 +1,80 @@
+use std::env;
+use std::time::Duration;
+
+use tixel::backend::TestBackend;
+use tixel::layer::BaseLayer;
+use tixel::theme::{Appearance, Theme};
+use tixel::{Color, HexColor};
+
+fn render(layer: &BaseLayer) -> TestBackend {
+    let (width, height) = layer.window_size;
+    let mut backend = TestBackend::new(width as u16, height as u16);
+    backend.render(layer).expect("layer renders");
+    backend
+}
+
+#[test]
+fn the_base_layer_paints_its_theme() {
+    let mut layer = BaseLayer::with_size((40, 30)).expect("layer fits");
+    let backend = render(&layer);
+    for (x_axis, y_axis) in [(0, 0), (20, 15), (39, 29)] {
+        backend.assert_fg(x_axis, y_axis, Some(HexColor::WHITE.into()));
+        backend.assert_bg(x_axis, y_axis, Some(HexColor::BLACK.into()));
+    }
+
+    // Themes applied later, e.g. on reload, are painted from the next frame on
+    layer.apply_theme(&Theme::light());
+    let backend = render(&layer);
+    backend.assert_fg(20, 15, Some(HexColor::BLACK.into()));
+    backend.assert_bg(20, 15, Some(HexColor::WHITE.into()));
+}
+
+#[test]
+fn backgrounds_are_classified_by_their_contrast() {
+    assert_eq!(Appearance::of(&HexColor::BLACK), Appearance::Dark);
+    assert_eq!(Appearance::of(&HexColor::WHITE), Appearance::Light);
+    assert_eq!(
+        Appearance::of(&HexColor::from_rgb(0x28, 0x2C, 0x34)),
+        Appearance::Dark
+    );
+    assert_eq!(
+        Appearance::of(&HexColor::from_rgb(0xFD, 0xF6, 0xE3)),
+        Appearance::Light
+    );
+
+    assert_eq!(
+        Theme::for_appearance(Appearance::Light).background_color,
+        Color::from(HexColor::WHITE)
+    );
+    assert_eq!(
+        Theme::for_appearance(Appearance::Dark).background_color,
+        Color::from(HexColor::BLACK)
+    );
+}
+
+// The only test touching `COLORFGBG`, so the tests running alongside never see it change
+#[test]
+fn colorfgbg_names_the_background_palette_index() {
+    let appearance = |value: Option<&str>| {
+        match value {
+            Some(value) => env::set_var("COLORFGBG", value),
+            None => env::remove_var("COLORFGBG"),
+        }
+        Appearance::from_env()
+    };
+
+    assert_eq!(appearance(Some("15;0")), Some(Appearance::Dark));
+    assert_eq!(appearance(Some("0;15")), Some(Appearance::Light));
+    // rxvt puts a third field in the middle, the background stays the last one
+    assert_eq!(appearance(Some("0;default;7")), Some(Appearance::Light));
+    assert_eq!(appearance(Some("15;default")), None);
+    assert_eq!(appearance(Some("")), None);
+    assert_eq!(appearance(None), None);
+
+    // Without an answer from the terminal, which gets no time to reply here, detection
+    // falls back to `COLORFGBG` and then to dark
+    env::set_var("COLORFGBG", "0;15");
+    assert_eq!(Appearance::detect(Duration::ZERO), Appearance::Light);
+    env::remove_var("COLORFGBG");
+    assert_eq!(Appearance::detect(Duration::ZERO), Appearance::Dark);
+}
@@ -0,0 +1,142 @@
+use std::io;
+
+use tixel::backend::{Backend, TestBackend};
+use tixel::buffer::{Buffer, Cell};
+use tixel::compositor::{Compositor, LayerKind};
+use tixel::layer::Layer;
+use tixel::layout::Rect;
+use tixel::Color;
+
+// Symbols of a one-row buffer, with "" in the second cell of wide characters
+fn symbols(buffer: &Buffer) -> Vec<&str> {
+    let area = buffer.area();
+    (area.x..area.x + area.width)
+        .map(|x_axis| buffer.get(x_axis, area.y).unwrap().symbol.as_str())
+        .collect()
+}
+
+// A row of `width` cells with `text` written from the first one
+fn row(width: u16, text: &str) -> Buffer {
+    let mut buffer = Buffer::new(Rect::new(0, 0, width, 1));
+    let mut x_axis = 0;
+    for symbol in text.chars() {
+        x_axis += buffer.set_symbol(x_axis, 0, &symbol.to_string(), Color::Reset);
+    }
+    buffer
+}
+
+// Draws `text` over its area, leaving the cells given as spaces transparent
+struct Text {
+    area: Rect,
+    text: &'static str,
+}
+
+impl Layer for Text {
+    fn area(&self) -> Rect {
+        self.area
+    }
+
+    fn draw(&self, buffer: &mut Buffer) -> Result<(), io::Error> {
+        let mut x_axis = self.area.x;
+        for symbol in self.text.chars() {
+            if symbol == ' ' {
+                if let Some(cell) = buffer.get_mut(x_axis, self.area.y) {
+                    *cell = Cell::transparent();
+                }
+                x_axis += 1;
+            } else {
+                x_axis += buffer.set_symbol(x_axis, self.area.y, &symbol.to_string(), Color::Reset);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn wide_characters_take_two_cells() {
+    let mut buffer = row(4, "");
+    assert_eq!(buffer.set_symbol(1, 0, "漢", Color::Red), 2);
+    assert_eq!(symbols(&buffer), [" ", "漢", "", " "]);
+
+    // The second cell carries the colors of the first, so the terminal paints both alike
+    let continuation = buffer.get(2, 0).unwrap();
+    assert!(continuation.is_continuation());
+    assert_eq!(continuation.width(), 0);
+    assert_eq!(continuation.fg, Some(Color::Red));
+}
+
+#[test]
+fn wide_characters_past_the_right_edge_become_spaces() {
+    let mut buffer = row(3, "ab");
+    assert_eq!(buffer.set_symbol(2, 0, "漢", Color::Reset), 1);
+    assert_eq!(symbols(&buffer), ["a", "b", " "]);
+
+    // Clusters made of several code points are measured as a whole
+    assert_eq!(buffer.set_symbol(1, 0, "👍🏽", Color::Reset), 2);
+    assert_eq!(symbols(&buffer), ["a", "👍🏽", ""]);
+}
+
+#[test]
+fn overwriting_either_half_blanks_the_other() {
+    let mut buffer = row(4, "漢字");
+    buffer.set_char(0, 0, 'a', Color::Reset);
+    assert_eq!(symbols(&buffer), ["a", " ", "字", ""]);
+
+    let mut buffer = row(4, "漢字");
+    buffer.set_char(3, 0, 'b', Color::Reset);
+    assert_eq!(symbols(&buffer), ["漢", "", " ", "b"]);
+}
+
+#[test]
+fn wide_characters_straddling_two_others_blank_both() {
+    let mut buffer = row(4, "漢字");
+    buffer.set_char(1, 0, '日', Color::Reset);
+    assert_eq!(symbols(&buffer), [" ", "日", "", " "]);
+
+    // A narrow character over the second cell leaves the wide one written after it whole
+    let mut buffer = row(5, "漢字a");
+    buffer.set_char(1, 0, 'x', Color::Reset);
+    assert_eq!(symbols(&buffer), [" ", "x", "字", "", "a"]);
+}
+
+#[test]
+fn box_drawing_lines_blank_wide_characters_they_cut() {
+    let mut buffer = row(4, "漢字");
+    buffer.set_line(2, 0, '│', Color::Reset);
+    assert_eq!(symbols(&buffer), ["漢", "", "│", " "]);
+}
+
+#[test]
+fn layers_covering_half_of_a_wide_character_blank_it() {
+    let mut compositor = Compositor::new(Rect::new(0, 0, 6, 1));
+    compositor.push(
+        LayerKind::Base,
+        Box::new(Text {
+            area: Rect::new(0, 0, 6, 1),
+            text: "漢字日",
+        }),
+    );
+    // Covers the second cell of 漢 and the first of 日, and lets 字 show through
+    compositor.push(
+        LayerKind::Popup,
+        Box::new(Text {
+            area: Rect::new(1, 0, 4, 1),
+            text: "x  y",
+        }),
+    );
+
+    let frame = compositor.compose().unwrap();
+    assert_eq!(symbols(&frame), [" ", "x", "字", "", "y", " "]);
+}
+
+#[test]
+fn terminals_drop_wide_characters_drawn_over_in_a_later_frame() {
+    let mut backend = TestBackend::new(4, 1);
+    backend.draw(&row(4, "漢字")).unwrap();
+
+    // Only the cell drawn into reaches the terminal, overwriting half of 字
+    let mut partial = Buffer::new(Rect::new(0, 0, 4, 1));
+    partial.set_char(2, 0, 'z', Color::Reset);
+    backend.draw(&partial).unwrap();
+    backend.assert_lines(&["漢z "]);
+}
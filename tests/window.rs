@@ -0,0 +1,237 @@
+use tixel::backend::TestBackend;
+use tixel::border::Border;
+use tixel::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use tixel::layer::Layer;
+use tixel::layout::Rect;
+use tixel::window::{Window, WindowId, WindowManager, WindowState};
+
+// An 80x25 terminal: the desktop takes the first 24 rows and the task bar the last one
+fn desktop() -> WindowManager {
+    WindowManager::new(Rect::new(0, 0, 80, 25))
+}
+
+fn window(title: &str, area: Rect) -> Window {
+    let border = Border::new()
+        .ascii_fallback(false)
+        .build()
+        .expect("valid border");
+    Window::new(title, area, border)
+}
+
+fn mouse(manager: &mut WindowManager, kind: MouseEventKind, column: u16, row: u16) -> bool {
+    manager.handle_event(&Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }))
+}
+
+// Press the left button at `from`, drag to `to` and release it there
+fn drag(manager: &mut WindowManager, from: (u16, u16), to: (u16, u16)) {
+    assert!(mouse(
+        manager,
+        MouseEventKind::Down(MouseButton::Left),
+        from.0,
+        from.1
+    ));
+    assert!(mouse(
+        manager,
+        MouseEventKind::Drag(MouseButton::Left),
+        to.0,
+        to.1
+    ));
+    assert!(mouse(
+        manager,
+        MouseEventKind::Up(MouseButton::Left),
+        to.0,
+        to.1
+    ));
+}
+
+// Press and release the left button, returning whether the press was handled
+fn click(manager: &mut WindowManager, column: u16, row: u16) -> bool {
+    let handled = mouse(
+        manager,
+        MouseEventKind::Down(MouseButton::Left),
+        column,
+        row,
+    );
+    mouse(manager, MouseEventKind::Up(MouseButton::Left), column, row);
+    handled
+}
+
+fn key(manager: &mut WindowManager, code: KeyCode, modifiers: KeyModifiers) -> bool {
+    manager.handle_event(&Event::Key(KeyEvent::new(code, modifiers)))
+}
+
+fn area(manager: &WindowManager, id: WindowId) -> Rect {
+    manager.get(id).expect("open window").area()
+}
+
+fn state(manager: &WindowManager, id: WindowId) -> WindowState {
+    manager.get(id).expect("open window").state()
+}
+
+#[test]
+fn dragging_the_left_edge_keeps_the_right_edge() {
+    let mut manager = desktop();
+    let id = manager.open(window("a", Rect::new(40, 2, 40, 10)));
+
+    drag(&mut manager, (40, 5), (50, 5));
+    assert_eq!(area(&manager, id), Rect::new(50, 2, 30, 10));
+
+    drag(&mut manager, (50, 5), (30, 5));
+    assert_eq!(area(&manager, id), Rect::new(30, 2, 50, 10));
+
+    // The window keeps its minimum width, 8 columns for the title bar buttons
+    drag(&mut manager, (30, 5), (79, 5));
+    assert_eq!(area(&manager, id), Rect::new(72, 2, 8, 10));
+}
+
+#[test]
+fn dragging_the_right_and_bottom_edges_keeps_the_top_left_corner() {
+    let mut manager = desktop();
+    let id = manager.open(window("a", Rect::new(10, 2, 20, 10)));
+
+    drag(&mut manager, (29, 5), (39, 5));
+    assert_eq!(area(&manager, id), Rect::new(10, 2, 30, 10));
+
+    drag(&mut manager, (20, 11), (20, 7));
+    assert_eq!(area(&manager, id), Rect::new(10, 2, 30, 6));
+
+    // The edges stop at the desktop, above the task bar
+    drag(&mut manager, (39, 7), (79, 24));
+    assert_eq!(area(&manager, id), Rect::new(10, 2, 70, 22));
+}
+
+#[test]
+fn dragging_the_top_corners_keeps_the_bottom_edge() {
+    let mut manager = desktop();
+    let id = manager.open(window("a", Rect::new(10, 5, 20, 10)));
+
+    drag(&mut manager, (10, 5), (5, 2));
+    assert_eq!(area(&manager, id), Rect::new(5, 2, 25, 13));
+
+    drag(&mut manager, (29, 2), (34, 0));
+    assert_eq!(area(&manager, id), Rect::new(5, 0, 30, 15));
+
+    // Never taller than the desktop, nor shorter than the title bar and one row
+    drag(&mut manager, (34, 0), (34, 20));
+    assert_eq!(area(&manager, id), Rect::new(5, 12, 30, 3));
+}
+
+#[test]
+fn dragging_the_title_bar_moves_the_window_inside_the_desktop() {
+    let mut manager = desktop();
+    let id = manager.open(window("a", Rect::new(10, 5, 20, 10)));
+
+    drag(&mut manager, (15, 5), (25, 8));
+    assert_eq!(area(&manager, id), Rect::new(20, 8, 20, 10));
+
+    drag(&mut manager, (25, 8), (79, 24));
+    assert_eq!(area(&manager, id), Rect::new(60, 14, 20, 10));
+}
+
+#[test]
+fn minimized_windows_are_restored_from_the_task_bar() {
+    let mut manager = desktop();
+    let first = manager.open(window("a", Rect::new(0, 0, 20, 10)));
+    let second = manager.open(window("b", Rect::new(30, 0, 20, 10)));
+
+    // The buttons end one column before the top right corner: [-] then [+]
+    assert!(click(&mut manager, 43, 0));
+    assert_eq!(state(&manager, second), WindowState::Minimized);
+    assert_eq!(manager.focused(), Some(first));
+
+    // The task bar lists " a " at column 0 and " b " at column 4
+    assert!(click(&mut manager, 5, 24));
+    assert_eq!(state(&manager, second), WindowState::Normal);
+    assert_eq!(manager.focused(), Some(second));
+
+    // Clicking the focused window's entry minimizes it again
+    assert!(click(&mut manager, 5, 24));
+    assert_eq!(state(&manager, second), WindowState::Minimized);
+}
+
+#[test]
+fn maximized_windows_fill_the_desktop_until_restored() {
+    let mut manager = desktop();
+    let id = manager.open(window("a", Rect::new(10, 5, 20, 10)));
+
+    assert!(click(&mut manager, 26, 5));
+    assert_eq!(state(&manager, id), WindowState::Maximized);
+
+    let mut backend = TestBackend::new(80, 25);
+    backend.render(&manager).expect("windows render");
+    backend.assert_text(0, 0, "┌ a ");
+    backend.assert_text(79, 23, "┘");
+
+    // Maximized windows are neither moved nor resized
+    for (column, row) in [(10, 0), (79, 23)] {
+        mouse(
+            &mut manager,
+            MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+        );
+        assert!(!mouse(
+            &mut manager,
+            MouseEventKind::Drag(MouseButton::Left),
+            40,
+            12
+        ));
+        mouse(&mut manager, MouseEventKind::Up(MouseButton::Left), 40, 12);
+    }
+    assert_eq!(state(&manager, id), WindowState::Maximized);
+
+    // The maximize button is at the same place on the maximized window
+    assert!(click(&mut manager, 76, 0));
+    assert_eq!(state(&manager, id), WindowState::Normal);
+    assert_eq!(area(&manager, id), Rect::new(10, 5, 20, 10));
+}
+
+#[test]
+fn keyboard_moves_and_resizes_the_focused_window() {
+    let mut manager = desktop();
+    let id = manager.open(window("a", Rect::new(10, 5, 20, 10)));
+    let alt_shift = KeyModifiers::ALT.union(KeyModifiers::SHIFT);
+
+    assert!(key(&mut manager, KeyCode::Right, KeyModifiers::ALT));
+    assert!(key(&mut manager, KeyCode::Up, KeyModifiers::ALT));
+    assert_eq!(area(&manager, id), Rect::new(11, 4, 20, 10));
+
+    assert!(key(&mut manager, KeyCode::Left, alt_shift));
+    assert!(key(&mut manager, KeyCode::Down, alt_shift));
+    assert_eq!(area(&manager, id), Rect::new(11, 4, 19, 11));
+
+    // Without Alt the keys are left to the layers below
+    assert!(!key(&mut manager, KeyCode::Right, KeyModifiers::NONE));
+    assert_eq!(area(&manager, id), Rect::new(11, 4, 19, 11));
+}
+
+#[test]
+fn keyboard_minimizes_maximizes_and_switches_windows() {
+    let mut manager = desktop();
+    let first = manager.open(window("a", Rect::new(0, 0, 20, 10)));
+    let second = manager.open(window("b", Rect::new(30, 0, 20, 10)));
+
+    assert!(key(&mut manager, KeyCode::Char('m'), KeyModifiers::ALT));
+    assert_eq!(state(&manager, second), WindowState::Maximized);
+    assert!(key(&mut manager, KeyCode::Char('m'), KeyModifiers::ALT));
+    assert_eq!(state(&manager, second), WindowState::Normal);
+
+    assert!(key(&mut manager, KeyCode::Char('n'), KeyModifiers::ALT));
+    assert_eq!(state(&manager, second), WindowState::Minimized);
+    assert_eq!(manager.focused(), Some(first));
+
+    assert!(key(&mut manager, KeyCode::Char('w'), KeyModifiers::ALT));
+    assert_eq!(manager.focused(), Some(second));
+    assert_eq!(state(&manager, second), WindowState::Normal);
+
+    assert!(key(&mut manager, KeyCode::Char('1'), KeyModifiers::ALT));
+    assert_eq!(manager.focused(), Some(first));
+    assert!(!key(&mut manager, KeyCode::Char('3'), KeyModifiers::ALT));
+}